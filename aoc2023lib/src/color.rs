@@ -0,0 +1,551 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Color {
+    r: f64,
+    g: f64,
+    b: f64,
+    a: f64,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const TRANSPARENT: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
+    pub const RED: Color = Color::hex(0xff0000);
+    pub const GREEN: Color = Color::hex(0x00ff00);
+    pub const BLUE: Color = Color::hex(0x0000ff);
+    pub const YELLOW: Color = Color::hex(0xffff00);
+    pub const CYAN: Color = Color::hex(0x00ffff);
+    pub const MAGENTA: Color = Color::hex(0xff00ff);
+    pub const GRAY: Color = Color::hex(0x808080);
+    pub const LIGHT_GRAY: Color = Color::hex(0xe6e6e6);
+    pub const DARK_GRAY: Color = Color::hex(0x333333);
+
+    pub const fn rgb(r: f64, g: f64, b: f64) -> Self {
+        Self::rgba(r, g, b, 1.0)
+    }
+    pub const fn rgba(r: f64, g: f64, b: f64, a: f64) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// `Color::hex(0xff8800)` is the same as `"#ff8800".parse()`, but usable in a `const`.
+    pub const fn hex(rgb: u32) -> Self {
+        Self::rgb(
+            ((rgb >> 16) & 0xff) as f64 / 255.0,
+            ((rgb >> 8) & 0xff) as f64 / 255.0,
+            (rgb & 0xff) as f64 / 255.0,
+        )
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, with or without the leading `#`.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid hex color {:?}", hex));
+        }
+        let expanded: String = match digits.len() {
+            3 | 4 => digits.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => digits.to_string(),
+            _ => return Err(anyhow!("Invalid hex color length {:?}", hex)),
+        };
+        let channels = (0..expanded.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&expanded[i..i + 2], 16).map(|c| c as f64 / 255.0))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Invalid hex color {:?}", hex))?;
+        Ok(match channels.as_slice() {
+            [r, g, b] => Self::rgb(*r, *g, *b),
+            [r, g, b, a] => Self::rgba(*r, *g, *b, *a),
+            other => unreachable!("Unexpected channel count {:?}", other),
+        })
+    }
+
+    /// `hue` in degrees, `saturation` and `lightness` in `0.0..=1.0`.
+    pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        Self::hsla(hue, saturation, lightness, 1.0)
+    }
+
+    pub fn hsla(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Self {
+        let hue = hue.rem_euclid(360.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+        let m = lightness - chroma / 2.0;
+        let (r, g, b) = match hue {
+            h if h < 60.0 => (chroma, x, 0.0),
+            h if h < 120.0 => (x, chroma, 0.0),
+            h if h < 180.0 => (0.0, chroma, x),
+            h if h < 240.0 => (0.0, x, chroma),
+            h if h < 300.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Self::rgba(r + m, g + m, b + m, alpha)
+    }
+
+    pub fn r(&self) -> f64 {
+        self.r
+    }
+    pub fn g(&self) -> f64 {
+        self.g
+    }
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+    pub fn a(&self) -> f64 {
+        self.a
+    }
+
+    /// Returns `(hue, saturation, lightness)`, see [`Color::hsl`].
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = match max {
+            m if m == self.r => 60.0 * ((self.g - self.b) / delta).rem_euclid(6.0),
+            m if m == self.g => 60.0 * ((self.b - self.r) / delta + 2.0),
+            _ => 60.0 * ((self.r - self.g) / delta + 4.0),
+        };
+        (hue, saturation, lightness)
+    }
+
+    pub fn to_hex(&self) -> String {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self.a {
+            a if a >= 1.0 => format!(
+                "#{:02x}{:02x}{:02x}",
+                channel(self.r),
+                channel(self.g),
+                channel(self.b)
+            ),
+            _ => format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                channel(self.r),
+                channel(self.g),
+                channel(self.b),
+                channel(self.a)
+            ),
+        }
+    }
+
+    pub fn with_alpha(&self, a: f64) -> Self {
+        Self { a, ..*self }
+    }
+
+    /// Linear interpolation of all four channels, `t = 0.0` is `self` and `t = 1.0` is `other`.
+    pub fn lerp(&self, other: &Color, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        Self::rgba(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+
+    /// Composites `self` on top of `background` ("over" operator).
+    pub fn blend_over(&self, background: &Color) -> Self {
+        let a = self.a + background.a * (1.0 - self.a);
+        if a == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let mix = |fg: f64, bg: f64| (fg * self.a + bg * background.a * (1.0 - self.a)) / a;
+        Self::rgba(
+            mix(self.r, background.r),
+            mix(self.g, background.g),
+            mix(self.b, background.b),
+            a,
+        )
+    }
+
+    pub fn lighten(&self, amount: f64) -> Self {
+        self.lerp(&Color::WHITE.with_alpha(self.a), amount)
+    }
+
+    pub fn darken(&self, amount: f64) -> Self {
+        self.lerp(&Color::BLACK.with_alpha(self.a), amount)
+    }
+
+    pub fn named(name: &str) -> Option<Self> {
+        Some(
+            match name.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+                "white" => Color::WHITE,
+                "black" => Color::BLACK,
+                "transparent" => Color::TRANSPARENT,
+                "red" => Color::RED,
+                "green" => Color::GREEN,
+                "blue" => Color::BLUE,
+                "yellow" => Color::YELLOW,
+                "cyan" => Color::CYAN,
+                "magenta" => Color::MAGENTA,
+                "gray" | "grey" => Color::GRAY,
+                "lightgray" | "lightgrey" => Color::LIGHT_GRAY,
+                "darkgray" | "darkgrey" => Color::DARK_GRAY,
+                _ => return None,
+            },
+        )
+    }
+}
+
+impl FromStr for Color {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Color::named(s) {
+            Some(color) => Ok(color),
+            None => Color::from_hex(s),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Palette {
+    name: &'static str,
+    colors: &'static [Color],
+}
+
+impl Palette {
+    /// Okabe & Ito's palette, distinguishable with all common forms of color blindness.
+    pub const OKABE_ITO: Palette = Palette::new(
+        "okabe-ito",
+        &[
+            Color::hex(0xe69f00),
+            Color::hex(0x56b4e9),
+            Color::hex(0x009e73),
+            Color::hex(0xf0e442),
+            Color::hex(0x0072b2),
+            Color::hex(0xd55e00),
+            Color::hex(0xcc79a7),
+            Color::hex(0x000000),
+        ],
+    );
+    /// Paul Tol's "bright" qualitative scheme, also colorblind safe.
+    pub const TOL_BRIGHT: Palette = Palette::new(
+        "tol-bright",
+        &[
+            Color::hex(0x4477aa),
+            Color::hex(0x66ccee),
+            Color::hex(0x228833),
+            Color::hex(0xccbb44),
+            Color::hex(0xee6677),
+            Color::hex(0xaa3377),
+            Color::hex(0xbbbbbb),
+        ],
+    );
+    pub const TABLEAU10: Palette = Palette::new(
+        "tableau10",
+        &[
+            Color::hex(0x4e79a7),
+            Color::hex(0xf28e2b),
+            Color::hex(0xe15759),
+            Color::hex(0x76b7b2),
+            Color::hex(0x59a14f),
+            Color::hex(0xedc948),
+            Color::hex(0xb07aa1),
+            Color::hex(0xff9da7),
+            Color::hex(0x9c755f),
+            Color::hex(0xbab0ac),
+        ],
+    );
+    pub const PASTEL: Palette = Palette::new(
+        "pastel",
+        &[
+            Color::rgb(0.9, 0.9, 1.0),
+            Color::rgb(0.8, 1.0, 0.8),
+            Color::rgb(1.0, 0.9, 0.9),
+            Color::rgb(1.0, 1.0, 0.9),
+            Color::rgb(0.9, 1.0, 1.0),
+            Color::rgb(1.0, 0.9, 1.0),
+        ],
+    );
+    pub const DARK: Palette = Palette::new(
+        "dark",
+        &[
+            Color::hex(0x2d3a6b),
+            Color::hex(0x25573a),
+            Color::hex(0x6b2d2d),
+            Color::hex(0x5e5a22),
+            Color::hex(0x22575e),
+            Color::hex(0x5e2257),
+        ],
+    );
+
+    /// Panics if `colors` is empty, at compile time for palettes in a `const`.
+    pub const fn new(name: &'static str, colors: &'static [Color]) -> Self {
+        assert!(!colors.is_empty(), "A palette needs at least one color");
+        Self { name, colors }
+    }
+
+    pub fn all() -> [Palette; 5] {
+        [
+            Palette::OKABE_ITO,
+            Palette::TOL_BRIGHT,
+            Palette::TABLEAU10,
+            Palette::PASTEL,
+            Palette::DARK,
+        ]
+    }
+
+    pub fn named(name: &str) -> Option<Palette> {
+        Palette::all().into_iter().find(|p| p.name == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn colors(&self) -> &'static [Color] {
+        self.colors
+    }
+
+    /// Wraps around, so any index gives a color.
+    pub fn get(&self, index: usize) -> Color {
+        self.colors[index % self.colors.len()]
+    }
+}
+
+/// A sequential color scale for mapping a value in `0.0..=1.0` to a color, interpolating
+/// linearly between evenly spaced stops.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorScale {
+    name: &'static str,
+    stops: &'static [Color],
+}
+
+impl ColorScale {
+    /// Matplotlib's viridis: perceptually uniform, colorblind safe and readable in grayscale.
+    pub const VIRIDIS: ColorScale = ColorScale::new(
+        "viridis",
+        &[
+            Color::hex(0x440154),
+            Color::hex(0x482878),
+            Color::hex(0x3e4989),
+            Color::hex(0x31688e),
+            Color::hex(0x26828e),
+            Color::hex(0x1f9e89),
+            Color::hex(0x35b779),
+            Color::hex(0x6ece58),
+            Color::hex(0xb5de2b),
+            Color::hex(0xfde725),
+        ],
+    );
+    pub const MAGMA: ColorScale = ColorScale::new(
+        "magma",
+        &[
+            Color::hex(0x000004),
+            Color::hex(0x1c1044),
+            Color::hex(0x4f127b),
+            Color::hex(0x812581),
+            Color::hex(0xb5367a),
+            Color::hex(0xe55964),
+            Color::hex(0xfb8761),
+            Color::hex(0xfec287),
+            Color::hex(0xfcfdbf),
+        ],
+    );
+    pub const CIVIDIS: ColorScale = ColorScale::new(
+        "cividis",
+        &[
+            Color::hex(0x00224e),
+            Color::hex(0x123570),
+            Color::hex(0x3b496c),
+            Color::hex(0x575d6d),
+            Color::hex(0x707173),
+            Color::hex(0x8a8779),
+            Color::hex(0xa69d75),
+            Color::hex(0xc4b56c),
+            Color::hex(0xe4cf5b),
+            Color::hex(0xfee838),
+        ],
+    );
+
+    /// Panics if `stops` is empty, at compile time for scales in a `const`.
+    pub const fn new(name: &'static str, stops: &'static [Color]) -> Self {
+        assert!(!stops.is_empty(), "A color scale needs at least one stop");
+        Self { name, stops }
+    }
+
+    pub fn all() -> [ColorScale; 3] {
+        [ColorScale::VIRIDIS, ColorScale::MAGMA, ColorScale::CIVIDIS]
+    }
+
+    pub fn named(name: &str) -> Option<ColorScale> {
+        ColorScale::all().into_iter().find(|s| s.name == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The color at `t`, clamped to `0.0..=1.0`.
+    pub fn at(&self, t: f64) -> Color {
+        let last = self.stops.len() - 1;
+        let position = t.clamp(0.0, 1.0) * last as f64;
+        let index = (position.floor() as usize).min(last.saturating_sub(1));
+        match last {
+            0 => self.stops[0],
+            _ => self.stops[index].lerp(&self.stops[index + 1], position - index as f64),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    pub cell_background: Color,
+    pub text: Color,
+    pub gridlines: Color,
+    pub highlight: Color,
+    pub accents: Palette,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            background: Color::rgb(0.9, 0.9, 0.9),
+            cell_background: Color::WHITE,
+            text: Color::BLACK,
+            gridlines: Color::rgba(0.0, 0.0, 0.0, 0.1),
+            highlight: Color::RED,
+            accents: Palette::PASTEL,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            background: Color::hex(0x111111),
+            cell_background: Color::hex(0x1e1e1e),
+            text: Color::hex(0xdddddd),
+            gridlines: Color::rgba(1.0, 1.0, 1.0, 0.1),
+            highlight: Color::hex(0xff5555),
+            accents: Palette::DARK,
+        }
+    }
+
+    pub fn accents(mut self, accents: Palette) -> Self {
+        self.accents = accents;
+        self
+    }
+
+    pub fn accent(&self, index: usize) -> Color {
+        self.accents.get(index)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::color::{Color, ColorScale, Palette};
+
+    fn assert_close(actual: Color, expected: Color) {
+        let channels = |c: Color| [c.r(), c.g(), c.b(), c.a()];
+        for (a, e) in channels(actual).iter().zip(channels(expected).iter()) {
+            assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Color::from_hex("#ff0000").unwrap(), Color::RED);
+        assert_eq!(Color::from_hex("0f0").unwrap(), Color::GREEN);
+        assert_eq!("#0000ff80".parse::<Color>().unwrap().a(), 128.0 / 255.0);
+        assert!(Color::from_hex("#12345").is_err());
+        assert!(Color::from_hex("#gggggg").is_err());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        assert_eq!(Color::from_hex("#4e79a7").unwrap().to_hex(), "#4e79a7");
+        assert_eq!(Color::hex(0x4e79a7).with_alpha(0.0).to_hex(), "#4e79a700");
+    }
+
+    #[test]
+    fn test_hsl() {
+        assert_close(Color::hsl(0.0, 1.0, 0.5), Color::RED);
+        assert_close(Color::hsl(120.0, 1.0, 0.5), Color::GREEN);
+        assert_close(Color::hsl(240.0, 1.0, 0.5), Color::BLUE);
+        let (h, s, l) = Color::hex(0x4e79a7).to_hsl();
+        assert_close(Color::hsl(h, s, l), Color::hex(0x4e79a7));
+    }
+
+    #[test]
+    fn test_lerp_and_blend() {
+        assert_close(
+            Color::BLACK.lerp(&Color::WHITE, 0.5),
+            Color::rgb(0.5, 0.5, 0.5),
+        );
+        assert_close(
+            Color::RED.with_alpha(0.5).blend_over(&Color::WHITE),
+            Color::rgb(1.0, 0.5, 0.5),
+        );
+    }
+
+    #[test]
+    fn test_named() {
+        assert_eq!("Light Gray".parse::<Color>().unwrap(), Color::LIGHT_GRAY);
+        assert_eq!(Palette::named("okabe-ito"), Some(Palette::OKABE_ITO));
+        assert_eq!(Palette::OKABE_ITO.get(8), Palette::OKABE_ITO.get(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one color")]
+    fn test_empty_palette_is_rejected() {
+        Palette::new("empty", &[]);
+    }
+
+    #[test]
+    fn test_single_color_palette() {
+        let palette = Palette::new("red", &[Color::RED]);
+        assert_eq!(palette.get(0), Color::RED);
+        assert_eq!(palette.get(usize::MAX), Color::RED);
+    }
+
+    #[test]
+    fn test_color_scale_ends_and_midpoints() {
+        let scale = ColorScale::VIRIDIS;
+        assert_eq!(scale.at(0.0), Color::hex(0x440154));
+        assert_eq!(scale.at(1.0), Color::hex(0xfde725));
+        assert_eq!(scale.at(-3.0), scale.at(0.0));
+        assert_eq!(scale.at(7.0), scale.at(1.0));
+        assert_close(
+            scale.at(1.0 / 18.0),
+            Color::hex(0x440154).lerp(&Color::hex(0x482878), 0.5),
+        );
+        assert_eq!(ColorScale::named("magma"), Some(ColorScale::MAGMA));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one stop")]
+    fn test_empty_color_scale_is_rejected() {
+        ColorScale::new("empty", &[]);
+    }
+
+    #[test]
+    fn test_single_stop_color_scale() {
+        let scale = ColorScale::new("red", &[Color::RED]);
+        assert_eq!(scale.at(0.0), Color::RED);
+        assert_eq!(scale.at(0.5), Color::RED);
+        assert_eq!(scale.at(1.0), Color::RED);
+    }
+
+    #[test]
+    fn test_color_scale_gets_lighter() {
+        for scale in ColorScale::all() {
+            let lightness = |t: f64| scale.at(t).to_hsl().2;
+            assert!(lightness(0.0) < lightness(0.5));
+            assert!(lightness(0.5) < lightness(1.0));
+        }
+    }
+}
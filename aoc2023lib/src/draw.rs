@@ -1,38 +1,37 @@
-use std::ops::Add;
+use std::ops::{Add, Sub};
 
 use anyhow::Result;
 use cairo;
 use cairo::{Content, Context, Operator};
-use pango::{Alignment, FontDescription};
 
-#[derive(Copy, Clone)]
-pub struct Color {
-    r: f64,
-    g: f64,
-    b: f64,
-    a: f64,
-}
+pub mod annotation;
+pub mod camera;
+pub mod chart;
+pub mod frames;
+pub mod grid;
+pub mod heatmap;
+pub mod legend;
+pub mod minimap;
+pub mod snapshot;
+pub mod text;
+pub mod timeline;
+
+pub use crate::color::{Color, Palette, Theme};
+use crate::draw::text::Text;
 
 impl Color {
-    pub const fn rgb(r: f64, g: f64, b: f64) -> Self {
-        Self::rgba(r, g, b, 1.0)
-    }
-    pub const fn rgba(r: f64, g: f64, b: f64, a: f64) -> Self {
-        Self { r, g, b, a }
-    }
-
     pub fn set_source_color(&self, context: &Context) {
-        context.set_source_rgba(self.r, self.g, self.b, self.a);
+        context.set_source_rgba(self.r(), self.g(), self.b(), self.a());
     }
 
     pub fn fill(&self, context: &Context) -> Result<()> {
-        context.set_source_rgba(self.r, self.g, self.b, self.a);
+        context.set_source_rgba(self.r(), self.g(), self.b(), self.a());
         context.fill()?;
         Ok(())
     }
 
     pub fn stroke(&self, context: &Context) -> Result<()> {
-        context.set_source_rgba(self.r, self.g, self.b, self.a);
+        context.set_source_rgba(self.r(), self.g(), self.b(), self.a());
         context.set_line_width(2.);
         context.stroke()?;
         Ok(())
@@ -73,6 +72,18 @@ impl Add for Point {
     }
 }
 
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct Rectangle {
     top_left: Point,
     width: f64,
@@ -152,24 +163,10 @@ pub fn draw_text_in_center_of_square(
     center: &Point,
     square_size: &f64,
 ) -> Result<()> {
-    let font_size: i32 = (square_size * (13.0 / square_size)).round() as i32;
-
-    let layout = pangocairo::create_layout(context);
-    let mut font_description = FontDescription::from_string("Inconsolata,Medium");
-    font_description.set_size(font_size * pango::SCALE);
-    layout.set_font_description(Some(&font_description));
-    layout.set_width(*square_size as i32);
-    layout.set_height(*square_size as i32);
-    layout.set_alignment(Alignment::Center);
-    layout.set_text(text);
-
-    let origin = Point::new(center.x(), center.y() - ((*square_size) / 2.0));
-
-    context.save()?;
-    context.move_to(origin.x(), origin.y());
-    text_color.set_source_color(context);
-    // context.show_text(text)?;
-    pangocairo::show_layout(context, &layout);
-    context.restore()?;
-    Ok(())
+    Text::create(text).color(text_color).draw_centered_on(
+        context,
+        *center,
+        *square_size,
+        *square_size,
+    )
 }
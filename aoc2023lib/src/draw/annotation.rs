@@ -0,0 +1,167 @@
+use anyhow::Result;
+use cairo::Context;
+
+use crate::draw::text::{Text, TextSize};
+use crate::draw::{Color, Draw, Point, Rectangle};
+
+/// A text box at `position` with an arrow pointing at `target`.
+#[derive(Clone)]
+pub struct Callout {
+    label: Text,
+    position: Point,
+    target: Point,
+    padding: f64,
+    background: Color,
+    border_color: Color,
+    arrow_color: Color,
+    arrow_head: f64,
+}
+
+impl Callout {
+    pub fn create(text: &str, position: Point, target: Point) -> Self {
+        Self {
+            label: Text::create(text).size(TextSize::Absolute(12.)),
+            position,
+            target,
+            padding: 4.,
+            background: Color::rgba(1., 1., 0.85, 0.95),
+            border_color: Color::DARK_GRAY,
+            arrow_color: Color::DARK_GRAY,
+            arrow_head: 8.,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.label = self.label.text(text);
+        self
+    }
+
+    /// Font, size and color of the text.
+    pub fn label(mut self, label: Text) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn border(mut self, color: Color) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    pub fn arrow(mut self, color: Color) -> Self {
+        self.arrow_color = color;
+        self
+    }
+
+    /// The same callout with its position and target mapped through `transform`, e.g. from
+    /// scene to screen coordinates. The text keeps its size.
+    pub fn transformed<F: Fn(Point) -> Point>(&self, transform: F) -> Self {
+        let mut callout = self.clone();
+        callout.position = transform(self.position);
+        callout.target = transform(self.target);
+        callout
+    }
+
+    /// Top left corner, width and height of the text box. The box fits the text, so relative
+    /// text sizes are taken relative to a 16 pixel line.
+    pub fn bounds(&self, context: &Context) -> (Point, f64, f64) {
+        let pixel_size = self.label.pixel_size(16.);
+        let (text_width, text_height) = self.label.measure(context, pixel_size);
+        (
+            self.position,
+            text_width + self.padding * 2.,
+            text_height + self.padding * 2.,
+        )
+    }
+
+    /// Where the arrow leaves the box: the point on its outline closest to the target, or
+    /// `None` if the target is inside the box.
+    pub fn arrow_start(&self, top_left: Point, width: f64, height: f64) -> Option<Point> {
+        let x = self.target.x().clamp(top_left.x(), top_left.x() + width);
+        let y = self.target.y().clamp(top_left.y(), top_left.y() + height);
+        match (x, y) == (self.target.x(), self.target.y()) {
+            true => None,
+            false => Some(Point::new(x, y)),
+        }
+    }
+
+    fn draw_arrow(&self, context: &Context, start: Point) -> Result<()> {
+        let direction = self.target - start;
+        let angle = direction.y().atan2(direction.x());
+        let head = |offset: f64| {
+            Point::new(
+                self.target.x() - self.arrow_head * (angle + offset).cos(),
+                self.target.y() - self.arrow_head * (angle + offset).sin(),
+            )
+        };
+        let (left, right) = (head(0.4), head(-0.4));
+
+        context.save()?;
+        self.arrow_color.set_source_color(context);
+        context.set_line_width(1.5);
+        context.move_to(start.x(), start.y());
+        context.line_to(self.target.x(), self.target.y());
+        context.stroke()?;
+        context.move_to(self.target.x(), self.target.y());
+        context.line_to(left.x(), left.y());
+        context.line_to(right.x(), right.y());
+        context.close_path();
+        context.fill()?;
+        context.restore()?;
+        Ok(())
+    }
+}
+
+impl Draw for Callout {
+    fn draw(&self, context: &Context) -> Result<()> {
+        let (top_left, width, height) = self.bounds(context);
+        if let Some(start) = self.arrow_start(top_left, width, height) {
+            self.draw_arrow(context, start)?;
+        }
+        let frame = Rectangle::create(top_left, width, height);
+        frame.fill(self.background).draw(context)?;
+        context.save()?;
+        frame.set_path(context);
+        self.border_color.set_source_color(context);
+        context.set_line_width(1.);
+        context.stroke()?;
+        context.restore()?;
+        self.label
+            .clone()
+            .size(TextSize::Absolute(self.label.pixel_size(16.)))
+            .draw_in(
+                context,
+                Point::new(top_left.x() + self.padding, top_left.y() + self.padding),
+                width - self.padding * 2.,
+                height - self.padding * 2.,
+            )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::draw::annotation::Callout;
+    use crate::draw::Point;
+
+    #[test]
+    fn test_arrow_starts_on_nearest_edge() {
+        let callout = Callout::create("sum", Point::new(0., 0.), Point::new(50., 5.));
+        let start = callout.arrow_start(Point::new(0., 0.), 20., 10.).unwrap();
+        assert_eq!((start.x(), start.y()), (20., 5.));
+    }
+
+    #[test]
+    fn test_no_arrow_when_target_is_inside() {
+        let callout = Callout::create("sum", Point::new(0., 0.), Point::new(5., 5.));
+        assert!(callout.arrow_start(Point::new(0., 0.), 20., 10.).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+use anyhow::Result;
+use cairo::{Context, ImageSurface};
+use tween::{
+    BackOut, CubicInOut, ExpoOut, Linear, QuadInOut, SineInOut, Tween, TweenValue, Tweener,
+};
+
+use crate::draw::{Color, Point};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Easing {
+    Linear,
+    QuadInOut,
+    CubicInOut,
+    SineInOut,
+    ExpoOut,
+    BackOut,
+}
+
+impl Easing {
+    fn boxed<V: TweenValue>(&self) -> Box<dyn Tween<V>> {
+        match self {
+            Easing::Linear => Box::new(Linear),
+            Easing::QuadInOut => Box::new(QuadInOut),
+            Easing::CubicInOut => Box::new(CubicInOut),
+            Easing::SineInOut => Box::new(SineInOut),
+            Easing::ExpoOut => Box::new(ExpoOut),
+            Easing::BackOut => Box::new(BackOut),
+        }
+    }
+}
+
+impl TweenValue for Point {
+    fn scale(self, scale: f32) -> Self {
+        Point::new(self.x() * scale as f64, self.y() * scale as f64)
+    }
+}
+
+struct Transition {
+    center: Tweener<Point, usize, Box<dyn Tween<Point>>>,
+    zoom: Tweener<f64, usize, Box<dyn Tween<f64>>>,
+}
+
+/// A viewport of `width` x `height` pixels looking at `center` of a larger scene.
+///
+/// Calling [`Camera::focus`] starts a transition which [`Camera::step`] advances one frame at a
+/// time, so every frame written while the camera is moving shows an eased in-between position.
+pub struct Camera {
+    width: f64,
+    height: f64,
+    center: Point,
+    zoom: f64,
+    easing: Easing,
+    transition_frames: usize,
+    background: Color,
+    transition: Option<Transition>,
+}
+
+impl Camera {
+    pub fn create(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            center: Point::new(width / 2., height / 2.),
+            zoom: 1.0,
+            easing: Easing::CubicInOut,
+            transition_frames: 10,
+            background: Color::rgb(0.9, 0.9, 0.9),
+            transition: None,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn transition_frames(mut self, transition_frames: usize) -> Self {
+        self.transition_frames = transition_frames;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Moves the camera immediately, cancelling any transition in progress.
+    pub fn look_at(&mut self, center: Point, zoom: f64) {
+        self.transition = None;
+        self.center = center;
+        self.zoom = zoom;
+    }
+
+    pub fn focus(&mut self, target: Point) {
+        self.focus_with_zoom(target, self.target_zoom());
+    }
+
+    pub fn zoom_to(&mut self, zoom: f64) {
+        self.focus_with_zoom(self.target_center(), zoom);
+    }
+
+    pub fn focus_with_zoom(&mut self, target: Point, zoom: f64) {
+        if self.transition_frames == 0 {
+            self.look_at(target, zoom);
+            return;
+        }
+        let easing = self.easing;
+        self.transition = Some(Transition {
+            center: Tweener::new(self.center, target, self.transition_frames, easing.boxed()),
+            zoom: Tweener::new(self.zoom, zoom, self.transition_frames, easing.boxed()),
+        });
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Advances the current transition by one frame.
+    pub fn step(&mut self) {
+        if let Some(transition) = self.transition.as_mut() {
+            self.center = transition.center.move_by(1);
+            self.zoom = transition.zoom.move_by(1);
+            if transition.center.is_finished() && transition.zoom.is_finished() {
+                self.transition = None;
+            }
+        }
+    }
+
+    /// Top left corner, width and height of the part of the scene that is currently visible.
+    pub fn visible_area(&self) -> (Point, f64, f64) {
+        let width = self.width / self.zoom;
+        let height = self.height / self.zoom;
+        (
+            Point::new(self.center.x() - width / 2., self.center.y() - height / 2.),
+            width,
+            height,
+        )
+    }
+
+    pub fn to_screen(&self, point: Point) -> Point {
+        let (top_left, _, _) = self.visible_area();
+        Point::new(
+            (point.x() - top_left.x()) * self.zoom,
+            (point.y() - top_left.y()) * self.zoom,
+        )
+    }
+
+    /// Transforms `context` so that scene coordinates drawn to it end up where the camera sees them.
+    pub fn apply(&self, context: &Context) {
+        let (top_left, _, _) = self.visible_area();
+        context.scale(self.zoom, self.zoom);
+        context.translate(-top_left.x(), -top_left.y());
+    }
+
+    pub fn render(&self, context: &Context, scene: &ImageSurface) -> Result<()> {
+        context.save()?;
+        context.rectangle(0., 0., self.width, self.height);
+        self.background.fill(context)?;
+        self.apply(context);
+        context.set_source_surface(scene, 0., 0.)?;
+        context.paint()?;
+        context.restore()?;
+        Ok(())
+    }
+
+    fn target_center(&self) -> Point {
+        match &self.transition {
+            Some(transition) => transition.center.final_value(),
+            None => self.center,
+        }
+    }
+
+    fn target_zoom(&self) -> f64 {
+        match &self.transition {
+            Some(transition) => transition.zoom.final_value(),
+            None => self.zoom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::draw::camera::{Camera, Easing};
+    use crate::draw::Point;
+
+    #[test]
+    fn test_focus_reaches_target() {
+        let mut camera = Camera::create(100., 100.).transition_frames(4);
+        camera.focus(Point::new(250., 150.));
+        let mut steps = 0;
+        while camera.is_moving() {
+            camera.step();
+            steps += 1;
+        }
+        assert_eq!(steps, 4);
+        assert_eq!((camera.center().x(), camera.center().y()), (250., 150.));
+    }
+
+    #[test]
+    fn test_linear_focus_is_halfway_after_half_the_frames() {
+        let mut camera = Camera::create(100., 100.)
+            .transition_frames(4)
+            .easing(Easing::Linear);
+        camera.look_at(Point::new(0., 0.), 1.0);
+        camera.focus_with_zoom(Point::new(100., 0.), 3.0);
+        camera.step();
+        camera.step();
+        assert!((camera.center().x() - 50.).abs() < 1e-3);
+        assert!((camera.zoom() - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_visible_area_shrinks_with_zoom() {
+        let mut camera = Camera::create(800., 600.);
+        camera.look_at(Point::new(400., 300.), 2.0);
+        let (top_left, width, height) = camera.visible_area();
+        assert_eq!(
+            (top_left.x(), top_left.y(), width, height),
+            (200., 150., 400., 300.)
+        );
+        let screen = camera.to_screen(Point::new(400., 300.));
+        assert_eq!((screen.x(), screen.y()), (400., 300.));
+    }
+}
@@ -0,0 +1,557 @@
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{Context as AnyhowContext, Result};
+use cairo::{Context, Format, ImageSurface};
+
+use crate::draw::legend::Legend;
+use crate::draw::minimap::Corner;
+use crate::draw::text::{HorizontalAlignment, Text, TextSize, VerticalAlignment};
+use crate::draw::{Color, Draw, Palette, Point, Rectangle};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SeriesKind {
+    Line,
+    Bar,
+    Scatter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    name: String,
+    kind: SeriesKind,
+    points: Vec<(f64, f64)>,
+    color: Option<Color>,
+}
+
+impl Series {
+    pub fn create(name: &str, kind: SeriesKind, points: Vec<(f64, f64)>) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            points,
+            color: None,
+        }
+    }
+
+    pub fn line(name: &str, points: Vec<(f64, f64)>) -> Self {
+        Self::create(name, SeriesKind::Line, points)
+    }
+
+    pub fn bar(name: &str, points: Vec<(f64, f64)>) -> Self {
+        Self::create(name, SeriesKind::Bar, points)
+    }
+
+    pub fn scatter(name: &str, points: Vec<(f64, f64)>) -> Self {
+        Self::create(name, SeriesKind::Scatter, points)
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// Roughly `count` evenly spaced "round" values (steps of 1, 2 or 5 times a power of ten)
+/// covering `min..=max`.
+pub fn nice_ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    if count == 0 || !min.is_finite() || !max.is_finite() || max <= min {
+        return vec![min];
+    }
+    let raw_step = (max - min) / count as f64;
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+    let step = [1., 2., 5., 10.]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw_step)
+        .unwrap_or(10. * magnitude);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// `min..max`, widened by 0.5 on both sides when it is empty so data can be mapped onto it.
+fn widen(min: f64, max: f64) -> (f64, f64) {
+    match min < max {
+        true => (min, max),
+        false => (min - 0.5, max + 0.5),
+    }
+}
+
+fn format_tick(value: f64) -> String {
+    match value.fract() == 0.0 && value.abs() < 1e15 {
+        true => format!("{}", value as i64),
+        false => format!("{:.2}", value)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+    }
+}
+
+pub struct Chart {
+    width: f64,
+    height: f64,
+    margin: f64,
+    title: Option<String>,
+    x_label: Option<String>,
+    y_label: Option<String>,
+    x_range: Option<(f64, f64)>,
+    y_range: Option<(f64, f64)>,
+    ticks: usize,
+    palette: Palette,
+    background: Color,
+    axis_color: Color,
+    legend: Option<Corner>,
+    series: Vec<Series>,
+}
+
+impl Chart {
+    pub fn create(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            margin: 50.,
+            title: None,
+            x_label: None,
+            y_label: None,
+            x_range: None,
+            y_range: None,
+            ticks: 5,
+            palette: Palette::OKABE_ITO,
+            background: Color::WHITE,
+            axis_color: Color::BLACK,
+            legend: Some(Corner::TopRight),
+            series: vec![],
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn x_label(mut self, label: &str) -> Self {
+        self.x_label = Some(label.to_string());
+        self
+    }
+
+    pub fn y_label(mut self, label: &str) -> Self {
+        self.y_label = Some(label.to_string());
+        self
+    }
+
+    pub fn x_range(mut self, min: f64, max: f64) -> Self {
+        self.x_range = Some((min, max));
+        self
+    }
+
+    pub fn y_range(mut self, min: f64, max: f64) -> Self {
+        self.y_range = Some((min, max));
+        self
+    }
+
+    pub fn ticks(mut self, ticks: usize) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn axis_color(mut self, color: Color) -> Self {
+        self.axis_color = color;
+        self
+    }
+
+    /// Where the legend with the names of the series goes, top right by default. Series
+    /// without a name are left out.
+    pub fn legend(mut self, corner: Corner) -> Self {
+        self.legend = Some(corner);
+        self
+    }
+
+    pub fn without_legend(mut self) -> Self {
+        self.legend = None;
+        self
+    }
+
+    pub fn series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Ranges of the x and y axis; explicit ranges win, otherwise they span all data points.
+    /// Bar charts always include zero on the y axis. Empty ranges, e.g. of a single point, are
+    /// widened by 0.5 on both sides.
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points = || self.series.iter().flat_map(|s| s.points.iter());
+        let extent = |values: Vec<f64>| {
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            match (min.is_finite(), max.is_finite()) {
+                (true, true) => (min, max),
+                _ => (0., 1.),
+            }
+        };
+        let x_range = self
+            .x_range
+            .unwrap_or_else(|| extent(points().map(|(x, _)| *x).collect()));
+        let y_range = self.y_range.unwrap_or_else(|| {
+            let mut ys: Vec<f64> = points().map(|(_, y)| *y).collect();
+            if self.series.iter().any(|s| s.kind == SeriesKind::Bar) {
+                ys.push(0.);
+            }
+            extent(ys)
+        });
+        (widen(x_range.0, x_range.1), widen(y_range.0, y_range.1))
+    }
+
+    /// Maps a data point to canvas coordinates.
+    pub fn to_canvas(&self, x: f64, y: f64) -> Point {
+        self.to_canvas_within(self.bounds(), x, y)
+    }
+
+    fn to_canvas_within(&self, bounds: ((f64, f64), (f64, f64)), x: f64, y: f64) -> Point {
+        let ((x_min, x_max), (y_min, y_max)) = bounds;
+        let plot_width = self.width - 2. * self.margin;
+        let plot_height = self.height - 2. * self.margin;
+        Point::new(
+            self.margin + (x - x_min) / (x_max - x_min) * plot_width,
+            self.height - self.margin - (y - y_min) / (y_max - y_min) * plot_height,
+        )
+    }
+
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let surface = ImageSurface::create(Format::ARgb32, self.width as i32, self.height as i32)?;
+        let context = Context::new(&surface)?;
+        self.draw(&context)?;
+        let mut file = File::create(path.as_ref())
+            .with_context(|| format!("Could not create {:?}", path.as_ref()))?;
+        surface
+            .write_to_png(&mut file)
+            .with_context(|| format!("Could not write chart to {:?}", path.as_ref()))?;
+        Ok(())
+    }
+
+    fn label(
+        &self,
+        text: &str,
+        horizontal: HorizontalAlignment,
+        vertical: VerticalAlignment,
+    ) -> Text {
+        Text::create(text)
+            .size(TextSize::Absolute(12.))
+            .color(self.axis_color)
+            .align(horizontal, vertical)
+    }
+
+    fn draw_axes(&self, context: &Context) -> Result<()> {
+        let bounds = self.bounds();
+        let ((x_min, x_max), (y_min, y_max)) = bounds;
+        let origin = Point::new(self.margin, self.height - self.margin);
+        let tick_length = 5.;
+
+        context.save()?;
+        self.axis_color.set_source_color(context);
+        context.set_line_width(1.);
+        context.move_to(origin.x(), self.margin);
+        context.line_to(origin.x(), origin.y());
+        context.line_to(self.width - self.margin, origin.y());
+        context.stroke()?;
+
+        for tick in nice_ticks(x_min, x_max, self.ticks) {
+            let at = self.to_canvas_within(bounds, tick, y_min);
+            context.move_to(at.x(), at.y());
+            context.line_to(at.x(), at.y() + tick_length);
+            context.stroke()?;
+            self.label(
+                format_tick(tick).as_str(),
+                HorizontalAlignment::Center,
+                VerticalAlignment::Top,
+            )
+            .draw_in(
+                context,
+                Point::new(at.x() - self.margin / 2., at.y() + tick_length),
+                self.margin,
+                self.margin / 2.,
+            )?;
+        }
+        for tick in nice_ticks(y_min, y_max, self.ticks) {
+            let at = self.to_canvas_within(bounds, x_min, tick);
+            context.move_to(at.x(), at.y());
+            context.line_to(at.x() - tick_length, at.y());
+            context.stroke()?;
+            self.label(
+                format_tick(tick).as_str(),
+                HorizontalAlignment::Right,
+                VerticalAlignment::Middle,
+            )
+            .draw_in(
+                context,
+                Point::new(0., at.y() - self.margin / 4.),
+                self.margin - 2. * tick_length,
+                self.margin / 2.,
+            )?;
+        }
+        context.restore()?;
+
+        if let Some(x_label) = &self.x_label {
+            self.label(
+                x_label,
+                HorizontalAlignment::Center,
+                VerticalAlignment::Bottom,
+            )
+            .draw_in(
+                context,
+                Point::new(self.margin, self.height - self.margin / 2.),
+                self.width - 2. * self.margin,
+                self.margin / 2.,
+            )?;
+        }
+        if let Some(y_label) = &self.y_label {
+            self.label(
+                y_label,
+                HorizontalAlignment::Left,
+                VerticalAlignment::Bottom,
+            )
+            .draw_in(
+                context,
+                Point::new(self.margin / 4., 0.),
+                self.width / 2.,
+                self.margin * 0.8,
+            )?;
+        }
+        if let Some(title) = &self.title {
+            self.label(title, HorizontalAlignment::Center, VerticalAlignment::Top)
+                .size(TextSize::Absolute(16.))
+                .draw_in(context, Point::new(0., 4.), self.width, self.margin)?;
+        }
+        Ok(())
+    }
+
+    fn series_color(&self, index: usize, series: &Series) -> Color {
+        series.color.unwrap_or_else(|| self.palette.get(index))
+    }
+
+    fn draw_legend(&self, context: &Context) -> Result<()> {
+        let Some(corner) = self.legend else {
+            return Ok(());
+        };
+        self.series
+            .iter()
+            .enumerate()
+            .filter(|(_, series)| !series.name.is_empty())
+            .fold(
+                Legend::create().corner(corner),
+                |legend, (index, series)| {
+                    legend.entry(self.series_color(index, series), series.name())
+                },
+            )
+            .draw(context, self.width, self.height)
+    }
+
+    fn draw_series(&self, context: &Context, index: usize, series: &Series) -> Result<()> {
+        let color = self.series_color(index, series);
+        let bounds = self.bounds();
+        let ((x_min, x_max), (y_min, _)) = bounds;
+        context.save()?;
+        match series.kind {
+            SeriesKind::Line => {
+                for (i, (x, y)) in series.points.iter().enumerate() {
+                    let at = self.to_canvas_within(bounds, *x, *y);
+                    match i {
+                        0 => context.move_to(at.x(), at.y()),
+                        _ => context.line_to(at.x(), at.y()),
+                    }
+                }
+                color.stroke(context)?;
+            }
+            SeriesKind::Scatter => {
+                for (x, y) in series.points.iter() {
+                    let at = self.to_canvas_within(bounds, *x, *y);
+                    context.new_sub_path();
+                    context.arc(at.x(), at.y(), 3., 0., 2. * std::f64::consts::PI);
+                }
+                color.fill(context)?;
+            }
+            SeriesKind::Bar => {
+                let plot_width = self.width - 2. * self.margin;
+                let bar_width = (plot_width / (series.points.len().max(1) as f64) * 0.8)
+                    .min(plot_width / (x_max - x_min) * 0.8);
+                let baseline = self.to_canvas_within(bounds, x_min, 0f64.max(y_min)).y();
+                for (x, y) in series.points.iter() {
+                    let top = self.to_canvas_within(bounds, *x, *y);
+                    Rectangle::create(
+                        Point::new(top.x() - bar_width / 2., top.y().min(baseline)),
+                        bar_width,
+                        (baseline - top.y()).abs(),
+                    )
+                    .fill(color)
+                    .draw(context)?;
+                }
+            }
+        }
+        context.restore()?;
+        Ok(())
+    }
+}
+
+impl Draw for Chart {
+    fn draw(&self, context: &Context) -> Result<()> {
+        Rectangle::create(Point::new(0., 0.), self.width, self.height)
+            .fill(self.background)
+            .draw(context)?;
+        context.save()?;
+        context.rectangle(
+            self.margin,
+            self.margin,
+            self.width - 2. * self.margin,
+            self.height - 2. * self.margin,
+        );
+        context.clip();
+        for (index, series) in self.series.iter().enumerate() {
+            self.draw_series(context, index, series)?;
+        }
+        context.restore()?;
+        self.draw_axes(context)?;
+        self.draw_legend(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cairo::{Context, Format, ImageSurface};
+
+    use crate::draw::chart::{nice_ticks, Chart, Series};
+    use crate::draw::{Color, Draw};
+
+    #[test]
+    fn test_nice_ticks() {
+        assert_eq!(nice_ticks(0., 10., 5), vec![0., 2., 4., 6., 8., 10.]);
+        assert_eq!(nice_ticks(0., 100., 4), vec![0., 50., 100.]);
+        assert_eq!(nice_ticks(3., 7., 4), vec![3., 4., 5., 6., 7.]);
+        assert_eq!(nice_ticks(5., 5., 4), vec![5.]);
+    }
+
+    #[test]
+    fn test_bounds_include_zero_for_bars() {
+        let chart = Chart::create(200., 100.).series(Series::bar("b", vec![(1., 5.), (2., 7.)]));
+        assert_eq!(chart.bounds(), ((1., 2.), (0., 7.)));
+        let chart = Chart::create(200., 100.).series(Series::line("l", vec![(1., 5.), (2., 7.)]));
+        assert_eq!(chart.bounds(), ((1., 2.), (5., 7.)));
+    }
+
+    #[test]
+    fn test_explicit_empty_range_is_widened() {
+        let chart = Chart::create(200., 100.)
+            .margin(10.)
+            .x_range(3., 3.)
+            .y_range(0., 10.);
+        assert_eq!(chart.bounds(), ((2.5, 3.5), (0., 10.)));
+        let center = chart.to_canvas(3., 5.);
+        assert_eq!((center.x(), center.y()), (100., 50.));
+    }
+
+    #[test]
+    fn test_single_point_is_centered() {
+        let chart = Chart::create(200., 100.)
+            .margin(10.)
+            .series(Series::scatter("s", vec![(2., 7.)]));
+        assert_eq!(chart.bounds(), ((1.5, 2.5), (6.5, 7.5)));
+        let center = chart.to_canvas(2., 7.);
+        assert_eq!((center.x(), center.y()), (100., 50.));
+    }
+
+    #[test]
+    fn test_to_canvas() {
+        let chart = Chart::create(200., 100.)
+            .margin(10.)
+            .x_range(0., 10.)
+            .y_range(0., 10.);
+        let bottom_left = chart.to_canvas(0., 0.);
+        let top_right = chart.to_canvas(10., 10.);
+        assert_eq!((bottom_left.x(), bottom_left.y()), (10., 90.));
+        assert_eq!((top_right.x(), top_right.y()), (190., 10.));
+    }
+
+    /// The pixel at `x`, `y` as `0xAARRGGBB`.
+    fn pixel(surface: &mut ImageSurface, x: usize, y: usize) -> u32 {
+        let stride = surface.stride() as usize;
+        let data = surface.data().unwrap();
+        let offset = y * stride + x * 4;
+        u32::from_ne_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut surface = ImageSurface::create(Format::ARgb32, 200, 100).unwrap();
+        let context = Context::new(&surface).unwrap();
+        Chart::create(200., 100.)
+            .margin(20.)
+            .x_range(0., 2.)
+            .y_range(0., 1.)
+            .background(Color::LIGHT_GRAY)
+            .title("t")
+            .x_label("x")
+            .y_label("y")
+            .series(Series::line("l", vec![(0., 0.), (1., 1.)]))
+            .series(Series::scatter("s", vec![(0., 1.)]))
+            .series(Series::bar("b", vec![(1., 1.)]).color(Color::BLUE))
+            .draw(&context)
+            .unwrap();
+        drop(context);
+        surface.flush();
+
+        // The bar spans x 68 to 132 and y 20 to 80.
+        assert_eq!(pixel(&mut surface, 100, 50), 0xff0000ff);
+        assert_eq!(pixel(&mut surface, 130, 75), 0xff0000ff);
+        // Inside the plot area, away from the series and the legend in the top right.
+        assert_eq!(pixel(&mut surface, 30, 30), 0xffe6e6e6);
+        // Outside the plot area.
+        assert_eq!(pixel(&mut surface, 195, 5), 0xffe6e6e6);
+        assert_eq!(pixel(&mut surface, 195, 95), 0xffe6e6e6);
+    }
+
+    fn render(chart: Chart) -> Vec<u8> {
+        let mut surface = ImageSurface::create(Format::ARgb32, 200, 100).unwrap();
+        let context = Context::new(&surface).unwrap();
+        chart.draw(&context).unwrap();
+        drop(context);
+        surface.flush();
+        let data = surface.data().unwrap().to_vec();
+        data
+    }
+
+    #[test]
+    fn test_legend_lists_named_series() {
+        let chart = |name: &str| {
+            Chart::create(200., 100.)
+                .margin(20.)
+                .series(Series::line(name, vec![(0., 0.), (1., 1.)]))
+        };
+        let unnamed = render(chart(""));
+        assert_ne!(render(chart("line")), unnamed);
+        assert_eq!(render(chart("line").without_legend()), unnamed);
+    }
+}
@@ -0,0 +1,325 @@
+use std::fs::{create_dir_all, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as AnyhowContext, Result};
+use cairo::{Context, Format, ImageSurface};
+use grid::Grid;
+
+use crate::draw::annotation::Callout;
+use crate::draw::camera::Camera;
+use crate::draw::grid::GridRenderer;
+use crate::draw::legend::Legend;
+use crate::draw::minimap::{Corner, Minimap};
+use crate::draw::timeline::FrameSchedule;
+use crate::draw::{Color, Draw, Rectangle};
+use crate::position::Position;
+use crate::visualize::Visualizer;
+
+type RoleColorFn<'a, R> = Box<dyn Fn(&R) -> Color + 'a>;
+
+/// A [`Visualizer`] that paints highlights onto a rendered grid and writes a PNG seen through
+/// a [`Camera`] for every checkpoint, plus the in-between frames of camera transitions.
+pub struct FrameWriter<'a, T, R> {
+    grid: Grid<T>,
+    renderer: GridRenderer<'a, T>,
+    role_color: RoleColorFn<'a, R>,
+    scene: ImageSurface,
+    context: Context,
+    camera: Camera,
+    minimap: Option<Minimap>,
+    legend: Option<Legend>,
+    counters: Legend,
+    counter_values: Vec<(String, i64)>,
+    callouts: Vec<Callout>,
+    focus_color: Color,
+    focus: Option<Position>,
+    schedule: Option<FrameSchedule>,
+    checkpoints: usize,
+    output_dir: PathBuf,
+    frame_counter: usize,
+}
+
+impl<'a, T, R> FrameWriter<'a, T, R> {
+    pub fn create<C, P>(
+        grid: Grid<T>,
+        renderer: GridRenderer<'a, T>,
+        role_color: C,
+        output_dir: P,
+    ) -> Result<Self>
+    where
+        C: Fn(&R) -> Color + 'a,
+        P: AsRef<Path>,
+    {
+        let scene = renderer.create_surface(&grid)?;
+        let context = Context::new(&scene)?;
+        renderer.draw(&context, &grid)?;
+        Ok(Self {
+            grid,
+            renderer,
+            role_color: Box::new(role_color),
+            scene,
+            context,
+            camera: Camera::create(800., 600.),
+            minimap: Some(Minimap::create(200.)),
+            legend: None,
+            counters: Legend::create().corner(Corner::TopRight),
+            counter_values: Vec::new(),
+            callouts: Vec::new(),
+            focus_color: Color::rgba(1., 0., 0., 0.5),
+            focus: None,
+            schedule: None,
+            checkpoints: 0,
+            output_dir: output_dir.as_ref().to_path_buf(),
+            frame_counter: 0,
+        })
+    }
+
+    pub fn camera(mut self, camera: Camera) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    pub fn minimap(mut self, minimap: Minimap) -> Self {
+        self.minimap = Some(minimap);
+        self
+    }
+
+    pub fn without_minimap(mut self) -> Self {
+        self.minimap = None;
+        self
+    }
+
+    pub fn legend(mut self, legend: Legend) -> Self {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Style and placement of the box the counters are listed in; its own entries are kept
+    /// above the counters.
+    pub fn counters(mut self, counters: Legend) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Adds a callout in scene coordinates, shown on every frame until cleared.
+    pub fn add_callout(&mut self, callout: Callout) {
+        self.callouts.push(callout);
+    }
+
+    pub fn clear_callouts(&mut self) {
+        self.callouts.clear();
+    }
+
+    /// Fill of the focused cell on the minimap.
+    pub fn focus_color(mut self, color: Color) -> Self {
+        self.focus_color = color;
+        self
+    }
+
+    /// How many frames each checkpoint gets, see [`crate::draw::timeline::Timeline`]. Without
+    /// one every checkpoint gets a single frame, plus camera transitions.
+    pub fn schedule(mut self, schedule: FrameSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.frame_counter
+    }
+
+    /// The scene as the camera currently sees it, with the minimap, callouts, legend and
+    /// counters on top.
+    pub fn render_frame(&self) -> Result<ImageSurface> {
+        let surface = ImageSurface::create(
+            Format::ARgb32,
+            self.camera.width() as i32,
+            self.camera.height() as i32,
+        )?;
+        let context = Context::new(&surface)?;
+        self.camera.render(&context, &self.scene)?;
+
+        if let Some(minimap) = self.minimap.as_ref() {
+            let focus_highlights: Vec<Rectangle> = self
+                .focus
+                .iter()
+                .map(|&position| {
+                    Rectangle::create(
+                        self.renderer.cell_top_left(position),
+                        self.renderer.get_square_size(),
+                        self.renderer.get_square_size(),
+                    )
+                    .fill(self.focus_color)
+                })
+                .collect();
+            minimap.draw(&context, &self.scene, &self.camera, &focus_highlights)?;
+        }
+        for callout in self.callouts.iter() {
+            callout
+                .transformed(|point| self.camera.to_screen(point))
+                .draw(&context)?;
+        }
+        if let Some(legend) = self.legend.as_ref() {
+            legend.draw(&context, self.camera.width(), self.camera.height())?;
+        }
+        let counters = self
+            .counter_values
+            .iter()
+            .fold(self.counters.clone(), |counters, (name, value)| {
+                counters.line(format!("{}: {}", name, value).as_str())
+            });
+        counters.draw(&context, self.camera.width(), self.camera.height())?;
+        drop(context);
+        Ok(surface)
+    }
+
+    /// Renders the current frame once and writes it `count` times.
+    fn write_frames(&mut self, count: usize) -> Result<()> {
+        create_dir_all(&self.output_dir)
+            .with_context(|| format!("Could not create {:?}", self.output_dir))?;
+        let surface = self.render_frame()?;
+        for _ in 0..count {
+            let filename = self
+                .output_dir
+                .join(format!("frame-{:05}.png", self.frame_counter));
+            self.frame_counter += 1;
+
+            eprintln!("Writing focused frame {:?}", filename);
+            let mut file =
+                File::create(&filename).context("Could not create focused frame output file")?;
+            surface
+                .write_to_png(&mut file)
+                .with_context(|| format!("Could not write focused frame to {:?}", filename))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, R> Visualizer<R> for FrameWriter<'a, T, R> {
+    fn highlight(&mut self, position: Position, role: R) -> Result<()> {
+        self.renderer.draw_cell_with_background(
+            &self.context,
+            &self.grid,
+            position,
+            (self.role_color)(&role),
+        )
+    }
+
+    /// The first focus cuts to `position`, later ones pan there over the next frames.
+    fn focus(&mut self, position: Position) -> Result<()> {
+        let target = self.renderer.cell_center(position);
+        match self.focus.replace(position) {
+            None => {
+                let zoom = self.camera.zoom();
+                self.camera.look_at(target, zoom)
+            }
+            Some(_) => self.camera.focus(target),
+        }
+        Ok(())
+    }
+
+    /// Writes frames until the camera has finished moving, then holds the last one until the
+    /// checkpoint has had its frames from the schedule. Checkpoints scheduled for no frames
+    /// are skipped and the camera carries on moving at the next one.
+    fn checkpoint(&mut self) -> Result<()> {
+        let scheduled = self
+            .schedule
+            .as_ref()
+            .map_or(1, |schedule| schedule.frames(self.checkpoints));
+        self.checkpoints += 1;
+        if scheduled == 0 {
+            return Ok(());
+        }
+        let mut written = 0;
+        loop {
+            self.camera.step();
+            self.write_frames(1)?;
+            written += 1;
+            if !self.camera.is_moving() {
+                break;
+            }
+        }
+        self.write_frames(scheduled.saturating_sub(written))
+    }
+
+    fn counter(&mut self, name: &str, value: i64) -> Result<()> {
+        match self.counter_values.iter_mut().find(|(n, _)| n == name) {
+            Some((_, current)) => *current = value,
+            None => self.counter_values.push((name.to_string(), value)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use grid::Grid;
+
+    use crate::draw::camera::Camera;
+    use crate::draw::frames::FrameWriter;
+    use crate::draw::grid::GridRenderer;
+    use crate::draw::timeline::FrameSchedule;
+    use crate::draw::Color;
+    use crate::position::Position;
+    use crate::visualize::Visualizer;
+
+    #[test]
+    fn test_checkpoint_writes_transition_frames() {
+        let dir = std::env::temp_dir().join("aoc2023lib-frame-writer");
+        let _ = std::fs::remove_dir_all(&dir);
+        let grid = Grid::from_vec(vec![0; 16], 4);
+        let mut frames = FrameWriter::create(
+            grid,
+            GridRenderer::create(|_: &i32| String::new()).square_size(4.),
+            |_: &bool| Color::RED,
+            &dir,
+        )
+        .unwrap()
+        .camera(Camera::create(8., 8.).transition_frames(3))
+        .without_minimap();
+
+        frames.focus(Position::new(0, 0)).unwrap();
+        frames.highlight(Position::new(0, 0), true).unwrap();
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 1);
+
+        frames.focus(Position::new(3, 3)).unwrap();
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 4);
+        assert!(dir.join("frame-00003.png").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_schedule_holds_and_skips_checkpoints() {
+        let dir = std::env::temp_dir().join("aoc2023lib-frame-writer-schedule");
+        let _ = std::fs::remove_dir_all(&dir);
+        let grid = Grid::from_vec(vec![0; 16], 4);
+        let mut frames = FrameWriter::create(
+            grid,
+            GridRenderer::create(|_: &i32| String::new()).square_size(4.),
+            |_: &bool| Color::RED,
+            &dir,
+        )
+        .unwrap()
+        .camera(Camera::create(8., 8.).transition_frames(3))
+        .without_minimap()
+        .schedule(FrameSchedule::from(vec![2, 0, 5]));
+
+        frames.focus(Position::new(0, 0)).unwrap();
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 2);
+
+        frames.focus(Position::new(3, 3)).unwrap();
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 2);
+
+        // Three transition frames, then held for two more.
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 7);
+
+        // Past the end of the schedule.
+        frames.checkpoint().unwrap();
+        assert_eq!(frames.frames_written(), 8);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,284 @@
+use anyhow::Result;
+use cairo::{Context, Format, ImageSurface};
+use grid::Grid;
+
+use crate::draw::text::Text;
+use crate::draw::{Color, Draw, Point, Rectangle, Theme};
+use crate::position::Position;
+
+type GlyphFn<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+type BackgroundFn<'a, T> = Box<dyn Fn(Position, &T) -> Option<Color> + 'a>;
+
+pub struct GridRenderer<'a, T> {
+    square_size: f64,
+    glyph: GlyphFn<'a, T>,
+    background: BackgroundFn<'a, T>,
+    base_color: Option<Color>,
+    text_color: Option<Color>,
+    label: Text,
+    gridlines: bool,
+    gridline_color: Option<Color>,
+    theme: Option<Theme>,
+}
+
+impl<'a, T> GridRenderer<'a, T> {
+    pub fn create<G>(glyph: G) -> Self
+    where
+        G: Fn(&T) -> String + 'a,
+    {
+        Self {
+            square_size: 20.0,
+            glyph: Box::new(glyph),
+            background: Box::new(|_, _| None),
+            base_color: None,
+            text_color: None,
+            label: Text::create(""),
+            gridlines: false,
+            gridline_color: None,
+            theme: None,
+        }
+    }
+
+    pub fn background<B>(mut self, background: B) -> Self
+    where
+        B: Fn(Position, &T) -> Option<Color> + 'a,
+    {
+        self.background = Box::new(background);
+        self
+    }
+
+    pub fn square_size(mut self, square_size: f64) -> Self {
+        self.square_size = square_size;
+        self
+    }
+
+    /// Picks the largest square size that fits the whole grid in `width` x `height`.
+    pub fn fit_to(mut self, grid: &Grid<T>, width: f64, height: f64) -> Self {
+        let cols = grid.cols().max(1) as f64;
+        let rows = grid.rows().max(1) as f64;
+        self.square_size = (width / cols).min(height / rows).floor().max(1.0);
+        self
+    }
+
+    pub fn base_color(mut self, color: Color) -> Self {
+        self.base_color = Some(color);
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    /// Colors not set explicitly come from `theme`, whichever of them is called first.
+    pub fn theme(mut self, theme: &Theme) -> Self {
+        self.theme = Some(*theme);
+        self
+    }
+
+    /// Font, size and alignment used for the glyphs; the text and color are set per cell.
+    pub fn label(mut self, label: Text) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Draws gridlines in `color`.
+    pub fn gridlines(mut self, color: Color) -> Self {
+        self.gridlines = true;
+        self.gridline_color = Some(color);
+        self
+    }
+
+    /// Draws gridlines in the theme's color, unless [`GridRenderer::gridlines`] set one.
+    pub fn show_gridlines(mut self, show: bool) -> Self {
+        self.gridlines = show;
+        self
+    }
+
+    fn resolve(&self, color: Option<Color>, from_theme: fn(&Theme) -> Color) -> Color {
+        color.unwrap_or_else(|| from_theme(&self.theme.unwrap_or_else(Theme::light)))
+    }
+
+    pub fn get_base_color(&self) -> Color {
+        self.resolve(self.base_color, |theme| theme.cell_background)
+    }
+
+    pub fn get_text_color(&self) -> Color {
+        self.resolve(self.text_color, |theme| theme.text)
+    }
+
+    /// The color gridlines are drawn in, `None` without gridlines.
+    pub fn get_gridline_color(&self) -> Option<Color> {
+        self.gridlines
+            .then(|| self.resolve(self.gridline_color, |theme| theme.gridlines))
+    }
+
+    pub fn get_square_size(&self) -> f64 {
+        self.square_size
+    }
+
+    pub fn surface_size(&self, grid: &Grid<T>) -> (i32, i32) {
+        let square_size = self.square_size.round() as usize;
+        (
+            (grid.cols() * square_size) as i32,
+            (grid.rows() * square_size) as i32,
+        )
+    }
+
+    pub fn create_surface(&self, grid: &Grid<T>) -> Result<ImageSurface> {
+        let (width, height) = self.surface_size(grid);
+        let surface = ImageSurface::create(Format::ARgb32, width, height)?;
+        let context = Context::new(&surface)?;
+        Rectangle::create(Point::new(0., 0.), width as f64, height as f64)
+            .fill(self.get_base_color())
+            .draw(&context)?;
+        Ok(surface)
+    }
+
+    pub fn cell_top_left(&self, position: Position) -> Point {
+        Point::new(
+            self.square_size * position.x() as f64,
+            self.square_size * position.y() as f64,
+        )
+    }
+
+    pub fn cell_center(&self, position: Position) -> Point {
+        self.cell_top_left(position) + Point::new(self.square_size / 2., self.square_size / 2.)
+    }
+
+    pub fn draw(&self, context: &Context, grid: &Grid<T>) -> Result<()> {
+        for position in Position::iter_grid(grid) {
+            self.draw_cell(context, grid, position)?;
+        }
+        Ok(())
+    }
+
+    pub fn draw_cell(&self, context: &Context, grid: &Grid<T>, position: Position) -> Result<()> {
+        let background = position
+            .grid_value(grid)
+            .and_then(|value| (self.background)(position, value));
+        self.draw_cell_with_optional_background(context, grid, position, background)
+    }
+
+    /// Redraws a cell with `background` in place of whatever the background mapper returns.
+    pub fn draw_cell_with_background(
+        &self,
+        context: &Context,
+        grid: &Grid<T>,
+        position: Position,
+        background: Color,
+    ) -> Result<()> {
+        self.draw_cell_with_optional_background(context, grid, position, Some(background))
+    }
+
+    pub fn highlight(&self, context: &Context, position: Position, color: Color) -> Result<()> {
+        Rectangle::create(
+            self.cell_top_left(position),
+            self.square_size,
+            self.square_size,
+        )
+        .stroke(color)
+        .draw(context)
+    }
+
+    fn draw_cell_with_optional_background(
+        &self,
+        context: &Context,
+        grid: &Grid<T>,
+        position: Position,
+        background: Option<Color>,
+    ) -> Result<()> {
+        let Some(value) = position.grid_value(grid) else {
+            return Ok(());
+        };
+        let square = Rectangle::create(
+            self.cell_top_left(position),
+            self.square_size,
+            self.square_size,
+        );
+
+        square.fill(self.get_base_color()).draw(context)?;
+        if let Some(background) = background {
+            square.fill(background).draw(context)?;
+        }
+        let glyph = (self.glyph)(value);
+        if !glyph.is_empty() {
+            self.label
+                .clone()
+                .text(glyph.as_str())
+                .color(self.get_text_color())
+                .draw_in(
+                    context,
+                    self.cell_top_left(position),
+                    self.square_size,
+                    self.square_size,
+                )?;
+        }
+        if let Some(gridline_color) = self.get_gridline_color() {
+            context.save()?;
+            square.set_path(context);
+            gridline_color.set_source_color(context);
+            context.set_line_width(1.);
+            context.stroke()?;
+            context.restore()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use grid::Grid;
+
+    use crate::draw::grid::GridRenderer;
+    use crate::draw::{Color, Theme};
+    use crate::position::Position;
+
+    #[test]
+    fn test_surface_size() {
+        let grid = Grid::from_vec(vec![1, 2, 3, 4, 5, 6], 3);
+        let renderer = GridRenderer::create(|v: &i32| v.to_string()).square_size(10.);
+        assert_eq!(renderer.surface_size(&grid), (30, 20));
+    }
+
+    #[test]
+    fn test_fit_to() {
+        let grid = Grid::from_vec(vec![1, 2, 3, 4, 5, 6], 3);
+        let renderer = GridRenderer::create(|v: &i32| v.to_string()).fit_to(&grid, 300., 100.);
+        assert_eq!(renderer.get_square_size(), 50.);
+    }
+
+    #[test]
+    fn test_cell_center() {
+        let renderer = GridRenderer::<i32>::create(|v| v.to_string()).square_size(10.);
+        let center = renderer.cell_center(Position::new(2, 1));
+        assert_eq!((center.x(), center.y()), (25., 15.));
+    }
+
+    #[test]
+    fn test_theme_and_explicit_colors_in_any_order() {
+        let theme = Theme::dark();
+        let before = GridRenderer::<i32>::create(|v| v.to_string())
+            .theme(&theme)
+            .gridlines(Color::RED)
+            .text_color(Color::BLUE);
+        let after = GridRenderer::<i32>::create(|v| v.to_string())
+            .gridlines(Color::RED)
+            .text_color(Color::BLUE)
+            .theme(&theme);
+        for renderer in [before, after] {
+            assert_eq!(renderer.get_gridline_color(), Some(Color::RED));
+            assert_eq!(renderer.get_text_color(), Color::BLUE);
+            assert_eq!(renderer.get_base_color(), theme.cell_background);
+        }
+    }
+
+    #[test]
+    fn test_gridlines_from_theme() {
+        let theme = Theme::dark();
+        let renderer = GridRenderer::<i32>::create(|v| v.to_string());
+        assert_eq!(renderer.get_gridline_color(), None);
+        let renderer = renderer.show_gridlines(true).theme(&theme);
+        assert_eq!(renderer.get_gridline_color(), Some(theme.gridlines));
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::Result;
+use cairo::Context;
+use grid::Grid;
+
+use crate::color::ColorScale;
+use crate::draw::grid::GridRenderer;
+use crate::draw::legend::Legend;
+use crate::draw::{Color, Draw, Rectangle};
+use crate::position::Position;
+use crate::visualize::Visualizer;
+
+/// Per-cell counts, e.g. how often a search visited each cell, drawn as a layer of colored
+/// cells on top of a [`GridRenderer`] scene. Cells that were never counted are left alone.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    counts: Grid<u64>,
+    scale: ColorScale,
+    logarithmic: bool,
+    opacity: f64,
+}
+
+impl Heatmap {
+    pub fn create(cols: usize, rows: usize) -> Self {
+        Self {
+            counts: Grid::new(rows, cols),
+            scale: ColorScale::VIRIDIS,
+            logarithmic: false,
+            opacity: 0.75,
+        }
+    }
+
+    pub fn for_grid<T>(grid: &Grid<T>) -> Self {
+        Self::create(grid.cols(), grid.rows())
+    }
+
+    pub fn scale(mut self, scale: ColorScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Map counts to colors on a log scale, for counts spanning orders of magnitude.
+    pub fn logarithmic(mut self) -> Self {
+        self.logarithmic = true;
+        self
+    }
+
+    /// How much of the grid below shows through; 1.0 hides it.
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Adds `amount` to the count of `position`; positions outside the grid are ignored.
+    pub fn add(&mut self, position: Position, amount: u64) {
+        if let Some(count) = self.counts.get_mut(position.y(), position.x()) {
+            *count += amount;
+        }
+    }
+
+    pub fn count(&self, position: Position) -> u64 {
+        position.grid_value(&self.counts).copied().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u64 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Where `count` falls on the color scale, from 0.0 for a single visit to 1.0 for the
+    /// busiest cell.
+    pub fn intensity(&self, count: u64) -> f64 {
+        let max = self.max();
+        if max <= 1 || count <= 1 {
+            return match count >= max {
+                true => 1.0,
+                false => 0.0,
+            };
+        }
+        let count = count.min(max) as f64;
+        match self.logarithmic {
+            true => count.ln() / (max as f64).ln(),
+            false => (count - 1.) / (max as f64 - 1.),
+        }
+    }
+
+    /// The color for `count`, or `None` for cells that were never counted.
+    pub fn color(&self, count: u64) -> Option<Color> {
+        match count {
+            0 => None,
+            count => Some(
+                self.scale
+                    .at(self.intensity(count))
+                    .with_alpha(self.opacity),
+            ),
+        }
+    }
+
+    /// The count that sits at `intensity` on the color scale, the inverse of
+    /// [`Heatmap::intensity`].
+    fn count_at(&self, intensity: f64) -> u64 {
+        let max = self.max().max(1) as f64;
+        let count = match self.logarithmic {
+            true => max.powf(intensity),
+            false => 1. + intensity * (max - 1.),
+        };
+        count.round() as u64
+    }
+
+    pub fn draw<T>(&self, context: &Context, renderer: &GridRenderer<T>) -> Result<()> {
+        let square_size = renderer.get_square_size();
+        for position in Position::iter_grid(&self.counts) {
+            if let Some(color) = self.color(self.count(position)) {
+                Rectangle::create(renderer.cell_top_left(position), square_size, square_size)
+                    .fill(color)
+                    .draw(context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A legend titled `title` with up to `steps` evenly spaced counts from 1 to the maximum.
+    pub fn legend(&self, title: &str, steps: usize) -> Legend {
+        let legend = Legend::create().line(title);
+        if self.max() == 0 {
+            return legend.line("none");
+        }
+        let steps = steps.max(2);
+        let mut counts: Vec<u64> = (0..steps)
+            .map(|step| self.count_at(step as f64 / (steps - 1) as f64))
+            .collect();
+        counts.dedup();
+        counts.into_iter().fold(legend, |legend, count| {
+            legend.entry(
+                self.scale.at(self.intensity(count)),
+                count.to_string().as_str(),
+            )
+        })
+    }
+}
+
+/// Counts every [`Visualizer::visit`], ignoring the other events.
+impl<R> Visualizer<R> for Heatmap {
+    fn highlight(&mut self, _position: Position, _role: R) -> Result<()> {
+        Ok(())
+    }
+
+    fn focus(&mut self, _position: Position) -> Result<()> {
+        Ok(())
+    }
+
+    fn checkpoint(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn visit(&mut self, position: Position) -> Result<()> {
+        self.add(position, 1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::color::ColorScale;
+    use crate::draw::heatmap::Heatmap;
+    use crate::position::Position;
+    use crate::visualize::Visualizer;
+
+    fn heatmap() -> Heatmap {
+        let mut heatmap = Heatmap::create(3, 2);
+        heatmap.add(Position::new(0, 0), 1);
+        heatmap.add(Position::new(1, 0), 10);
+        heatmap.add(Position::new(2, 1), 100);
+        heatmap.add(Position::new(5, 5), 1000);
+        heatmap
+    }
+
+    #[test]
+    fn test_counts_ignore_positions_outside_grid() {
+        let heatmap = heatmap();
+        assert_eq!(heatmap.count(Position::new(1, 0)), 10);
+        assert_eq!(heatmap.count(Position::new(5, 5)), 0);
+        assert_eq!(heatmap.max(), 100);
+        assert_eq!(heatmap.total(), 111);
+    }
+
+    #[test]
+    fn test_linear_and_logarithmic_intensity() {
+        let linear = heatmap();
+        assert_eq!(linear.intensity(1), 0.0);
+        assert_eq!(linear.intensity(100), 1.0);
+        assert!((linear.intensity(10) - 9. / 99.).abs() < 1e-9);
+
+        let logarithmic = heatmap().logarithmic();
+        assert!((logarithmic.intensity(10) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_uncounted_cells_have_no_color() {
+        let heatmap = heatmap().scale(ColorScale::MAGMA).opacity(1.0);
+        assert_eq!(heatmap.color(0), None);
+        assert_eq!(heatmap.color(100), Some(ColorScale::MAGMA.at(1.0)));
+    }
+
+    #[test]
+    fn test_legend_steps_are_deduplicated() {
+        let mut heatmap = Heatmap::create(1, 1);
+        assert_eq!(heatmap.legend("visits", 5).len(), 2);
+        heatmap.add(Position::new(0, 0), 2);
+        // Title plus 1 and 2, the steps in between round to one of those.
+        assert_eq!(heatmap.legend("visits", 5).len(), 3);
+    }
+
+    #[test]
+    fn test_visits_are_counted() {
+        let mut heatmap = Heatmap::create(2, 2);
+        Visualizer::<()>::visit(&mut heatmap, Position::new(1, 1)).unwrap();
+        Visualizer::<()>::visit(&mut heatmap, Position::new(1, 1)).unwrap();
+        Visualizer::<()>::highlight(&mut heatmap, Position::new(0, 0), ()).unwrap();
+        assert_eq!(heatmap.count(Position::new(1, 1)), 2);
+        assert_eq!(heatmap.count(Position::new(0, 0)), 0);
+    }
+}
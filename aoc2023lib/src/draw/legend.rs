@@ -0,0 +1,215 @@
+use anyhow::Result;
+use cairo::Context;
+
+use crate::draw::minimap::Corner;
+use crate::draw::text::{HorizontalAlignment, Text, TextSize, VerticalAlignment};
+use crate::draw::{Color, Draw, Point, Rectangle};
+
+/// A box in a corner of the frame listing what colors mean, one row per entry. Entries without
+/// a color are plain lines of text, e.g. running counters.
+#[derive(Clone)]
+pub struct Legend {
+    entries: Vec<(Option<Color>, String)>,
+    corner: Corner,
+    margin: f64,
+    row_height: f64,
+    padding: f64,
+    label: Text,
+    background: Option<Color>,
+    border_color: Option<Color>,
+}
+
+impl Legend {
+    pub fn create() -> Self {
+        Self {
+            entries: Vec::new(),
+            corner: Corner::BottomLeft,
+            margin: 10.,
+            row_height: 18.,
+            padding: 6.,
+            label: Text::create("")
+                .size(TextSize::Absolute(12.))
+                .align(HorizontalAlignment::Left, VerticalAlignment::Middle),
+            background: Some(Color::rgba(1., 1., 1., 0.85)),
+            border_color: Some(Color::rgba(0., 0., 0., 0.2)),
+        }
+    }
+
+    pub fn entry(mut self, color: Color, label: &str) -> Self {
+        self.entries.push((Some(color), label.to_string()));
+        self
+    }
+
+    pub fn line(mut self, label: &str) -> Self {
+        self.entries.push((None, label.to_string()));
+        self
+    }
+
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn row_height(mut self, row_height: f64) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    pub fn padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Font, size and color of the entry labels; the text is set per entry.
+    pub fn label(mut self, label: Text) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn without_background(mut self) -> Self {
+        self.background = None;
+        self
+    }
+
+    pub fn border(mut self, color: Color) -> Self {
+        self.border_color = Some(color);
+        self
+    }
+
+    pub fn without_border(mut self) -> Self {
+        self.border_color = None;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn swatch_size(&self) -> f64 {
+        self.row_height * 0.7
+    }
+
+    fn has_swatches(&self) -> bool {
+        self.entries.iter().any(|(color, _)| color.is_some())
+    }
+
+    /// Width and height of the whole box.
+    pub fn size(&self, context: &Context) -> (f64, f64) {
+        let pixel_size = self.label.pixel_size(self.row_height);
+        let text_width = self
+            .entries
+            .iter()
+            .map(|(_, label)| {
+                self.label
+                    .clone()
+                    .text(label)
+                    .measure(context, pixel_size)
+                    .0
+            })
+            .fold(0., f64::max);
+        let swatch_width = match self.has_swatches() {
+            true => self.swatch_size() + self.padding,
+            false => 0.,
+        };
+        (
+            self.padding * 2. + swatch_width + text_width,
+            self.padding * 2. + self.row_height * self.entries.len() as f64,
+        )
+    }
+
+    /// Draws the legend in its corner of an `output_width` x `output_height` frame.
+    pub fn draw(&self, context: &Context, output_width: f64, output_height: f64) -> Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        let (width, height) = self.size(context);
+        let top_left = self
+            .corner
+            .place(width, height, output_width, output_height, self.margin);
+
+        let frame = Rectangle::create(top_left, width, height);
+        if let Some(background) = self.background {
+            frame.fill(background).draw(context)?;
+        }
+        if let Some(border_color) = self.border_color {
+            context.save()?;
+            frame.set_path(context);
+            border_color.set_source_color(context);
+            context.set_line_width(1.);
+            context.stroke()?;
+            context.restore()?;
+        }
+
+        let swatch_size = self.swatch_size();
+        let text_left = match self.has_swatches() {
+            true => top_left.x() + self.padding * 2. + swatch_size,
+            false => top_left.x() + self.padding,
+        };
+        for (index, (color, label)) in self.entries.iter().enumerate() {
+            let row_top = top_left.y() + self.padding + self.row_height * index as f64;
+            if let Some(color) = color {
+                Rectangle::create(
+                    Point::new(
+                        top_left.x() + self.padding,
+                        row_top + (self.row_height - swatch_size) / 2.,
+                    ),
+                    swatch_size,
+                    swatch_size,
+                )
+                .fill(*color)
+                .draw(context)?;
+            }
+            self.label.clone().text(label).draw_in(
+                context,
+                Point::new(text_left, row_top),
+                width - (text_left - top_left.x()) - self.padding,
+                self.row_height,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cairo::{Context, Format, ImageSurface};
+
+    use crate::draw::legend::Legend;
+    use crate::draw::Color;
+
+    #[test]
+    fn test_size_grows_with_entries() {
+        let surface = ImageSurface::create(Format::ARgb32, 10, 10).unwrap();
+        let context = Context::new(&surface).unwrap();
+        let one = Legend::create().entry(Color::RED, "symbol");
+        let two = one.clone().entry(Color::BLUE, "part number");
+        let (one_width, one_height) = one.size(&context);
+        let (two_width, two_height) = two.size(&context);
+        assert_eq!(one_height, 6. * 2. + 18.);
+        assert_eq!(two_height, 6. * 2. + 18. * 2.);
+        assert!(two_width > one_width);
+    }
+
+    #[test]
+    fn test_lines_have_no_swatch_space() {
+        let surface = ImageSurface::create(Format::ARgb32, 10, 10).unwrap();
+        let context = Context::new(&surface).unwrap();
+        let (with_swatch, _) = Legend::create().entry(Color::RED, "x").size(&context);
+        let (without_swatch, _) = Legend::create().line("x").size(&context);
+        assert!(with_swatch > without_swatch);
+    }
+}
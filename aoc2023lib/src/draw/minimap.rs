@@ -0,0 +1,211 @@
+use anyhow::Result;
+use cairo::{Context, ImageSurface};
+
+use crate::draw::camera::Camera;
+use crate::draw::{Color, Draw, Point, Rectangle};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// Top left corner of a `width` x `height` box placed in this corner of an
+    /// `output_width` x `output_height` frame, `margin` away from its edges.
+    pub fn place(
+        &self,
+        width: f64,
+        height: f64,
+        output_width: f64,
+        output_height: f64,
+        margin: f64,
+    ) -> Point {
+        let left = margin;
+        let right = output_width - width - margin;
+        let top = margin;
+        let bottom = output_height - height - margin;
+        match self {
+            Corner::TopLeft => Point::new(left, top),
+            Corner::TopRight => Point::new(right, top),
+            Corner::BottomLeft => Point::new(left, bottom),
+            Corner::BottomRight => Point::new(right, bottom),
+        }
+    }
+}
+
+/// A scaled down copy of the whole scene drawn on top of a camera frame, with the part of the
+/// scene the camera currently sees outlined.
+pub struct Minimap {
+    corner: Corner,
+    size: f64,
+    margin: f64,
+    preserve_aspect_ratio: bool,
+    background: Option<Color>,
+    border_color: Option<Color>,
+    viewport_color: Color,
+}
+
+impl Minimap {
+    pub fn create(size: f64) -> Self {
+        Self {
+            corner: Corner::TopLeft,
+            size,
+            margin: 0.,
+            preserve_aspect_ratio: true,
+            background: None,
+            border_color: Some(Color::rgba(0., 0., 0., 0.1)),
+            viewport_color: Color::rgb(0., 0., 0.),
+        }
+    }
+
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Stretch the scene to a `size` x `size` square instead of keeping its aspect ratio.
+    pub fn stretch(mut self) -> Self {
+        self.preserve_aspect_ratio = false;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn border(mut self, color: Color) -> Self {
+        self.border_color = Some(color);
+        self
+    }
+
+    pub fn without_border(mut self) -> Self {
+        self.border_color = None;
+        self
+    }
+
+    pub fn viewport_color(mut self, color: Color) -> Self {
+        self.viewport_color = color;
+        self
+    }
+
+    /// Horizontal and vertical factor the scene is scaled by to fit in the minimap.
+    pub fn scale(&self, scene_width: f64, scene_height: f64) -> (f64, f64) {
+        let scale_x = self.size / scene_width;
+        let scale_y = self.size / scene_height;
+        match self.preserve_aspect_ratio {
+            true => (scale_x.min(scale_y), scale_x.min(scale_y)),
+            false => (scale_x, scale_y),
+        }
+    }
+
+    /// Top left corner, width and height of the minimap within an `output_width` x
+    /// `output_height` frame.
+    pub fn bounds(
+        &self,
+        scene_width: f64,
+        scene_height: f64,
+        output_width: f64,
+        output_height: f64,
+    ) -> (Point, f64, f64) {
+        let (scale_x, scale_y) = self.scale(scene_width, scene_height);
+        let width = scene_width * scale_x;
+        let height = scene_height * scale_y;
+        let top_left = self
+            .corner
+            .place(width, height, output_width, output_height, self.margin);
+        (top_left, width, height)
+    }
+
+    /// Draws the minimap onto a frame rendered by `camera`. `highlights` are given in scene
+    /// coordinates and are drawn on top of the scaled scene.
+    pub fn draw(
+        &self,
+        context: &Context,
+        scene: &ImageSurface,
+        camera: &Camera,
+        highlights: &[Rectangle],
+    ) -> Result<()> {
+        let scene_width = scene.width() as f64;
+        let scene_height = scene.height() as f64;
+        let (scale_x, scale_y) = self.scale(scene_width, scene_height);
+        let (top_left, width, height) =
+            self.bounds(scene_width, scene_height, camera.width(), camera.height());
+
+        context.save()?;
+        context.translate(top_left.x(), top_left.y());
+        context.rectangle(0., 0., width, height);
+        context.clip();
+        if let Some(background) = self.background {
+            context.rectangle(0., 0., width, height);
+            background.fill(context)?;
+        }
+
+        context.save()?;
+        context.scale(scale_x, scale_y);
+        context.set_source_surface(scene, 0., 0.)?;
+        context.paint()?;
+        for highlight in highlights {
+            highlight.draw(context)?;
+        }
+        let (visible_top_left, visible_width, visible_height) = camera.visible_area();
+        context.rectangle(
+            visible_top_left.x(),
+            visible_top_left.y(),
+            visible_width,
+            visible_height,
+        );
+        self.viewport_color.set_source_color(context);
+        context.set_line_width(2. / scale_x.min(scale_y));
+        context.stroke()?;
+        context.restore()?;
+
+        if let Some(border_color) = self.border_color {
+            context.rectangle(0.5, 0.5, width - 1., height - 1.);
+            border_color.set_source_color(context);
+            context.set_line_width(1.);
+            context.stroke()?;
+        }
+        context.restore()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::draw::minimap::{Corner, Minimap};
+
+    #[test]
+    fn test_preserves_aspect_ratio() {
+        let (top_left, width, height) = Minimap::create(200.).bounds(400., 100., 800., 600.);
+        assert_eq!(
+            (top_left.x(), top_left.y(), width, height),
+            (0., 0., 200., 50.)
+        );
+    }
+
+    #[test]
+    fn test_stretch() {
+        let (_, width, height) = Minimap::create(200.)
+            .stretch()
+            .bounds(400., 100., 800., 600.);
+        assert_eq!((width, height), (200., 200.));
+    }
+
+    #[test]
+    fn test_bottom_right_corner_with_margin() {
+        let (top_left, _, _) = Minimap::create(100.)
+            .corner(Corner::BottomRight)
+            .margin(10.)
+            .bounds(100., 100., 800., 600.);
+        assert_eq!((top_left.x(), top_left.y()), (690., 490.));
+    }
+}
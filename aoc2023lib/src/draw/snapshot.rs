@@ -0,0 +1,386 @@
+use std::fs::{create_dir_all, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use cairo::{Context, Format, ImageSurface};
+use pango::prelude::FontExt;
+use pango::FontDescription;
+
+use crate::draw::{Color, Draw, Point, Rectangle};
+
+/// Result of comparing two images pixel by pixel.
+pub struct Comparison {
+    pub differing_pixels: usize,
+    pub max_difference: u8,
+    pub diff: ImageSurface,
+}
+
+/// Compares rendered scenes against reference PNGs stored in a directory.
+///
+/// References are only written when `UPDATE_SNAPSHOTS` is set; a missing reference fails and
+/// writes `<name>.actual.png`, so a snapshot that was never committed can't pass on CI. On a
+/// mismatch `<name>.actual.png` and `<name>.diff.png` are written next to the reference, with
+/// differing pixels painted red in the diff.
+///
+/// Scenes with text should pin a font family that is installed wherever the snapshots run, see
+/// [`Snapshot::require_font`], and allow for anti-aliasing that differs with the FreeType
+/// version through [`Snapshot::tolerance`] and [`Snapshot::max_differing_pixels`].
+pub struct Snapshot {
+    dir: PathBuf,
+    tolerance: u8,
+    max_differing_pixels: usize,
+}
+
+impl Snapshot {
+    pub fn create<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            tolerance: 2,
+            max_differing_pixels: 0,
+        }
+    }
+
+    /// Largest per-channel difference (0-255) that still counts as the same pixel.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn max_differing_pixels(mut self, max_differing_pixels: usize) -> Self {
+        self.max_differing_pixels = max_differing_pixels;
+        self
+    }
+
+    /// Fails unless pango resolves `family` to itself instead of substituting another font, so
+    /// a missing font shows up as such rather than as a snapshot mismatch.
+    pub fn require_font(family: &str) -> Result<()> {
+        let surface = ImageSurface::create(Format::ARgb32, 1, 1)?;
+        let context = Context::new(&surface)?;
+        let pango_context = pangocairo::create_context(&context);
+        let font = pango_context
+            .load_font(&FontDescription::from_string(family))
+            .ok_or_else(|| anyhow!("Could not load any font for {:?}", family))?;
+        let resolved = font.describe().family().unwrap_or_default();
+        match resolved.as_str() == family {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "Font {:?} is not installed, pango picked {:?}",
+                family,
+                resolved
+            )),
+        }
+    }
+
+    /// Renders `draw` onto a white `width` x `height` in-memory surface.
+    pub fn render<F>(width: i32, height: i32, draw: F) -> Result<ImageSurface>
+    where
+        F: FnOnce(&Context) -> Result<()>,
+    {
+        let surface = ImageSurface::create(Format::ARgb32, width, height)?;
+        let context = Context::new(&surface)?;
+        Rectangle::create(Point::new(0., 0.), width as f64, height as f64)
+            .fill(Color::WHITE)
+            .draw(&context)?;
+        draw(&context)?;
+        drop(context);
+        surface.flush();
+        Ok(surface)
+    }
+
+    pub fn assert_draw_matches<D: Draw>(
+        &self,
+        name: &str,
+        width: i32,
+        height: i32,
+        drawable: &D,
+    ) -> Result<()> {
+        let surface = Snapshot::render(width, height, |context| drawable.draw(context))?;
+        self.assert_matches(name, &surface)
+    }
+
+    pub fn assert_matches(&self, name: &str, actual: &ImageSurface) -> Result<()> {
+        let reference_path = self.path(name, "png");
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            return write_png(actual, &reference_path);
+        }
+        if !reference_path.exists() {
+            let actual_path = self.path(name, "actual.png");
+            write_png(actual, &actual_path)?;
+            return Err(anyhow!(
+                "Snapshot {:?} has no reference {:?}, see {:?} and rerun with UPDATE_SNAPSHOTS=1",
+                name,
+                reference_path,
+                actual_path
+            ));
+        }
+        let mut reference_file = File::open(&reference_path)
+            .with_context(|| format!("Could not open reference {:?}", reference_path))?;
+        let expected = ImageSurface::create_from_png(&mut reference_file)
+            .with_context(|| format!("Could not read reference {:?}", reference_path))?;
+
+        if (actual.width(), actual.height()) != (expected.width(), expected.height()) {
+            write_png(actual, &self.path(name, "actual.png"))?;
+            return Err(anyhow!(
+                "Snapshot {:?} is {}x{}, reference is {}x{}",
+                name,
+                actual.width(),
+                actual.height(),
+                expected.width(),
+                expected.height()
+            ));
+        }
+
+        let comparison = compare(actual, &expected, self.tolerance)?;
+        if comparison.differing_pixels > self.max_differing_pixels {
+            let diff_path = self.path(name, "diff.png");
+            write_png(actual, &self.path(name, "actual.png"))?;
+            write_png(&comparison.diff, &diff_path)?;
+            return Err(anyhow!(
+                "Snapshot {:?} differs from reference in {} pixels (max difference {}), see {:?}",
+                name,
+                comparison.differing_pixels,
+                comparison.max_difference,
+                diff_path
+            ));
+        }
+        Ok(())
+    }
+
+    fn path(&self, name: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", name, extension))
+    }
+}
+
+/// Compares two equally sized images and paints a diff: differing pixels in red, scaled by how
+/// much they differ, everything else as a faded copy of `expected`.
+pub fn compare(
+    actual: &ImageSurface,
+    expected: &ImageSurface,
+    tolerance: u8,
+) -> Result<Comparison> {
+    let width = actual.width();
+    let height = actual.height();
+    let actual_pixels = pixels(actual)?;
+    let expected_pixels = pixels(expected)?;
+
+    let mut differing_pixels = 0;
+    let mut max_difference = 0u8;
+    let mut diff_data: Vec<u8> = Vec::with_capacity(actual_pixels.len());
+    for (a, e) in actual_pixels.chunks(4).zip(expected_pixels.chunks(4)) {
+        let difference = a
+            .iter()
+            .zip(e.iter())
+            .map(|(a, e)| a.abs_diff(*e))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        // Pixels are stored as native endian u32 ARGB, i.e. BGRA in memory on little endian.
+        let [b, g, r, a] = match difference > tolerance {
+            true => {
+                differing_pixels += 1;
+                [0, 0, 128u8.saturating_add(difference / 2), 255]
+            }
+            false => {
+                let gray = 192 + ((e[0] as u16 + e[1] as u16 + e[2] as u16) / 12) as u8;
+                [gray, gray, gray, 255]
+            }
+        };
+        let pixel = u32::from_le_bytes([b, g, r, a]).to_ne_bytes();
+        diff_data.extend_from_slice(&pixel);
+    }
+
+    let diff = ImageSurface::create_for_data(diff_data, Format::ARgb32, width, height, width * 4)?;
+    Ok(Comparison {
+        differing_pixels,
+        max_difference,
+        diff,
+    })
+}
+
+/// Pixel data as 4 bytes per pixel without row padding; `RGB24` images get an opaque alpha.
+fn pixels(surface: &ImageSurface) -> Result<Vec<u8>> {
+    let width = surface.width() as usize;
+    let height = surface.height() as usize;
+    let stride = surface.stride() as usize;
+    let format = surface.format();
+    let mut pixels: Vec<u8> = Vec::with_capacity(width * height * 4);
+    surface
+        .with_data(|data| {
+            for row in data.chunks(stride).take(height) {
+                for pixel in row[..width * 4].chunks(4) {
+                    let mut value = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                    if format == Format::Rgb24 {
+                        value |= 0xff000000;
+                    }
+                    pixels.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        })
+        .map_err(|err| anyhow!("Could not read surface data: {:?}", err))?;
+    Ok(pixels)
+}
+
+fn write_png(surface: &ImageSurface, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let mut file = File::create(path).with_context(|| format!("Could not create {:?}", path))?;
+    surface
+        .write_to_png(&mut file)
+        .with_context(|| format!("Could not write {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use grid::Grid;
+
+    use crate::draw::camera::Camera;
+    use crate::draw::grid::GridRenderer;
+    use crate::draw::minimap::{Corner, Minimap};
+    use crate::draw::snapshot::{compare, write_png, Snapshot};
+    use crate::draw::text::{HorizontalAlignment, Text, TextSize, VerticalAlignment};
+    use crate::draw::{Color, Draw, Point, Rectangle};
+    use crate::position::Position;
+
+    fn snapshot() -> Snapshot {
+        Snapshot::create(concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots"))
+    }
+
+    #[test]
+    fn test_compare_counts_differing_pixels() {
+        let white = Snapshot::render(4, 4, |_| Ok(())).unwrap();
+        let dotted = Snapshot::render(4, 4, |context| {
+            Rectangle::create(Point::new(0., 0.), 2., 1.)
+                .fill(Color::BLACK)
+                .draw(context)
+        })
+        .unwrap();
+        let comparison = compare(&dotted, &white, 2).unwrap();
+        assert_eq!(comparison.differing_pixels, 2);
+        assert_eq!(comparison.max_difference, 255);
+        assert_eq!(compare(&white, &white, 0).unwrap().differing_pixels, 0);
+    }
+
+    #[test]
+    fn test_mismatch_writes_actual_and_diff() {
+        let dir = std::env::temp_dir().join("aoc2023lib-snapshot-mismatch");
+        let _ = std::fs::remove_dir_all(&dir);
+        let white = Snapshot::render(4, 4, |_| Ok(())).unwrap();
+        let black = Snapshot::render(4, 4, |context| {
+            Rectangle::create(Point::new(0., 0.), 4., 4.)
+                .fill(Color::BLACK)
+                .draw(context)
+        })
+        .unwrap();
+        let snapshot = Snapshot::create(&dir);
+        write_png(&white, &dir.join("square.png")).unwrap();
+        snapshot.assert_matches("square", &white).unwrap();
+        assert!(snapshot.assert_matches("square", &black).is_err());
+        assert!(dir.join("square.actual.png").exists());
+        assert!(dir.join("square.diff.png").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_reference_fails() {
+        let dir = std::env::temp_dir().join("aoc2023lib-snapshot-missing");
+        let _ = std::fs::remove_dir_all(&dir);
+        let white = Snapshot::render(4, 4, |_| Ok(())).unwrap();
+        let snapshot = Snapshot::create(&dir);
+        assert!(snapshot.assert_matches("missing", &white).is_err());
+        assert!(!dir.join("missing.png").exists());
+        assert!(dir.join("missing.actual.png").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rectangle_snapshot() {
+        let rectangle = Rectangle::create(Point::new(4., 4.), 24., 16.)
+            .fill(Color::hex(0x56b4e9))
+            .stroke(Color::BLACK);
+        snapshot()
+            .assert_draw_matches("rectangle", 32, 24, &rectangle)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_grid_renderer_snapshot() {
+        let grid = Grid::from_vec(vec![0, 1, 2, 3, 4, 5], 3);
+        let renderer = GridRenderer::create(|_: &i32| String::new())
+            .square_size(10.)
+            .gridlines(Color::rgba(0., 0., 0., 0.2))
+            .background(|_, value| match value % 2 {
+                0 => Some(Color::hex(0xe69f00)),
+                _ => None,
+            });
+        let surface = Snapshot::render(30, 20, |context| {
+            renderer.draw(context, &grid)?;
+            renderer.highlight(context, Position::new(1, 1), Color::RED)
+        })
+        .unwrap();
+        snapshot()
+            .assert_matches("grid_renderer", &surface)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_minimap_snapshot() {
+        let scene = Snapshot::render(80, 40, |context| {
+            Rectangle::create(Point::new(0., 0.), 40., 40.)
+                .fill(Color::hex(0x009e73))
+                .draw(context)
+        })
+        .unwrap();
+        let mut camera = Camera::create(60., 40.);
+        camera.look_at(Point::new(40., 20.), 2.0);
+        let surface = Snapshot::render(60, 40, |context| {
+            camera.render(context, &scene)?;
+            Minimap::create(20.)
+                .corner(Corner::BottomRight)
+                .margin(2.)
+                .draw(context, &scene, &camera, &[])
+        })
+        .unwrap();
+        snapshot().assert_matches("minimap", &surface).unwrap();
+    }
+
+    /// Installed by the fonts-dejavu-core package, and the fallback family of [`Text`].
+    const FONT: &str = "DejaVu Sans Mono";
+
+    #[test]
+    fn test_require_font() {
+        Snapshot::require_font(FONT).unwrap();
+        assert!(Snapshot::require_font("No Such Font Family").is_err());
+    }
+
+    #[test]
+    fn test_labeled_grid_snapshot() {
+        Snapshot::require_font(FONT).unwrap();
+        let grid = Grid::from_vec("467..*35.".chars().collect(), 3);
+        let renderer = GridRenderer::create(|c: &char| c.to_string())
+            .square_size(16.)
+            .label(Text::create("").families(&[FONT]))
+            .gridlines(Color::rgba(0., 0., 0., 0.2))
+            .background(|_, c| match c {
+                '*' => Some(Color::hex(0xe69f00)),
+                _ => None,
+            });
+        let surface = Snapshot::render(48, 64, |context| {
+            renderer.draw(context, &grid)?;
+            Text::create("Σ 12")
+                .families(&[FONT])
+                .size(TextSize::Absolute(11.))
+                .color(Color::hex(0x0072b2))
+                .align(HorizontalAlignment::Left, VerticalAlignment::Middle)
+                .draw_in(context, Point::new(2., 48.), 44., 16.)
+        })
+        .unwrap();
+        // Room for anti-aliasing differences along glyph edges, not for different glyphs.
+        snapshot()
+            .tolerance(32)
+            .max_differing_pixels(8)
+            .assert_matches("labeled_grid", &surface)
+            .unwrap();
+    }
+}
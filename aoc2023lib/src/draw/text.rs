@@ -0,0 +1,219 @@
+use anyhow::Result;
+use cairo::Context;
+use pango::{EllipsizeMode, FontDescription, Layout};
+
+use crate::draw::{Color, Point};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HorizontalAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VerticalAlignment {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextSize {
+    /// Font size in pixels.
+    Absolute(f64),
+    /// Font size as a fraction of the height of the box the text is drawn in.
+    Relative(f64),
+}
+
+/// What to do with text that is wider than the box it is drawn in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Overflow {
+    Visible,
+    Shrink,
+    Truncate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Text {
+    text: String,
+    families: Vec<String>,
+    style: String,
+    size: TextSize,
+    color: Color,
+    horizontal: HorizontalAlignment,
+    vertical: VerticalAlignment,
+    overflow: Overflow,
+}
+
+impl Text {
+    pub fn create(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            families: vec![
+                "Inconsolata".to_string(),
+                "DejaVu Sans Mono".to_string(),
+                "monospace".to_string(),
+            ],
+            style: "Medium".to_string(),
+            size: TextSize::Relative(0.8),
+            color: Color::BLACK,
+            horizontal: HorizontalAlignment::Center,
+            vertical: VerticalAlignment::Middle,
+            overflow: Overflow::Visible,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Font families in order of preference; pango falls back to the next one for glyphs the
+    /// previous ones don't have.
+    pub fn families(mut self, families: &[&str]) -> Self {
+        self.families = families.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    /// Pango style and weight words, e.g. `"Bold Italic"`.
+    pub fn style(mut self, style: &str) -> Self {
+        self.style = style.to_string();
+        self
+    }
+
+    pub fn size(mut self, size: TextSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn align(mut self, horizontal: HorizontalAlignment, vertical: VerticalAlignment) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn font_description(&self, pixel_size: f64) -> FontDescription {
+        let mut font_description = FontDescription::from_string(
+            format!("{} {}", self.families.join(","), self.style).as_str(),
+        );
+        font_description.set_absolute_size(pixel_size * pango::SCALE as f64);
+        font_description
+    }
+
+    pub fn pixel_size(&self, box_height: f64) -> f64 {
+        match self.size {
+            TextSize::Absolute(size) => size,
+            TextSize::Relative(fraction) => box_height * fraction,
+        }
+    }
+
+    /// Width and height in pixels of the text set at `pixel_size`, without any wrapping.
+    pub fn measure(&self, context: &Context, pixel_size: f64) -> (f64, f64) {
+        let layout = self.layout(context, pixel_size);
+        let (_, logical) = layout.pixel_extents();
+        (logical.width() as f64, logical.height() as f64)
+    }
+
+    /// Draws the text aligned within the box at `top_left` of `width` x `height`.
+    pub fn draw_in(
+        &self,
+        context: &Context,
+        top_left: Point,
+        width: f64,
+        height: f64,
+    ) -> Result<()> {
+        let mut pixel_size = self.pixel_size(height);
+        if self.overflow == Overflow::Shrink {
+            let (text_width, _) = self.measure(context, pixel_size);
+            if text_width > width && text_width > 0. {
+                pixel_size *= width / text_width;
+            }
+        }
+
+        let layout = self.layout(context, pixel_size);
+        if self.overflow == Overflow::Truncate {
+            layout.set_width((width * pango::SCALE as f64) as i32);
+            layout.set_ellipsize(EllipsizeMode::End);
+        }
+        let (_, logical) = layout.pixel_extents();
+        let text_width = logical.width() as f64;
+        let text_height = logical.height() as f64;
+
+        let x = match self.horizontal {
+            HorizontalAlignment::Left => top_left.x(),
+            HorizontalAlignment::Center => top_left.x() + (width - text_width) / 2.,
+            HorizontalAlignment::Right => top_left.x() + width - text_width,
+        } - logical.x() as f64;
+        let y = match self.vertical {
+            VerticalAlignment::Top => top_left.y(),
+            VerticalAlignment::Middle => top_left.y() + (height - text_height) / 2.,
+            VerticalAlignment::Bottom => top_left.y() + height - text_height,
+        } - logical.y() as f64;
+
+        context.save()?;
+        context.move_to(x, y);
+        self.color.set_source_color(context);
+        pangocairo::show_layout(context, &layout);
+        context.restore()?;
+        Ok(())
+    }
+
+    pub fn draw_centered_on(
+        &self,
+        context: &Context,
+        center: Point,
+        width: f64,
+        height: f64,
+    ) -> Result<()> {
+        let top_left = Point::new(center.x() - width / 2., center.y() - height / 2.);
+        self.draw_in(context, top_left, width, height)
+    }
+
+    fn layout(&self, context: &Context, pixel_size: f64) -> Layout {
+        let layout = pangocairo::create_layout(context);
+        layout.set_font_description(Some(&self.font_description(pixel_size)));
+        layout.set_text(self.text.as_str());
+        layout
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cairo::{Context, Format, ImageSurface};
+
+    use crate::draw::text::{Text, TextSize};
+
+    #[test]
+    fn test_pixel_size_scales_with_box() {
+        let text = Text::create("1");
+        assert_eq!(text.pixel_size(20.), 16.);
+        assert_eq!(text.pixel_size(40.), 32.);
+        assert_eq!(text.size(TextSize::Absolute(9.)).pixel_size(40.), 9.);
+    }
+
+    #[test]
+    fn test_measure_grows_with_text_and_size() {
+        let surface = ImageSurface::create(Format::ARgb32, 10, 10).unwrap();
+        let context = Context::new(&surface).unwrap();
+        let (short, _) = Text::create("1").measure(&context, 13.);
+        let (long, _) = Text::create("1234").measure(&context, 13.);
+        let (large, _) = Text::create("1").measure(&context, 26.);
+        assert!(long > short);
+        assert!(large > short);
+    }
+}
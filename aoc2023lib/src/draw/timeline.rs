@@ -0,0 +1,275 @@
+use crate::visualize::{Event, EventLog};
+
+type KeyFrameFn<'a, R> = Box<dyn Fn(&Event<R>) -> bool + 'a>;
+
+/// The kinds of events a step between two checkpoints can contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventKind {
+    Highlight,
+    Focus,
+    Counter,
+    Visit,
+}
+
+impl EventKind {
+    fn of<R>(event: &Event<R>) -> Option<Self> {
+        match event {
+            Event::Highlight { .. } => Some(EventKind::Highlight),
+            Event::Focus(_) => Some(EventKind::Focus),
+            Event::Counter { .. } => Some(EventKind::Counter),
+            Event::Visit(_) => Some(EventKind::Visit),
+            Event::Checkpoint => None,
+        }
+    }
+}
+
+/// How many frames each checkpoint of an event log is shown for; 0 skips the checkpoint, its
+/// changes then show up in the next frame that is written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameSchedule {
+    frames: Vec<usize>,
+}
+
+impl FrameSchedule {
+    /// Frames for the checkpoint at `index`, 1 for checkpoints past the end of the schedule.
+    pub fn frames(&self, index: usize) -> usize {
+        self.frames.get(index).copied().unwrap_or(1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn total_frames(&self) -> usize {
+        self.frames.iter().sum()
+    }
+}
+
+impl From<Vec<usize>> for FrameSchedule {
+    fn from(frames: Vec<usize>) -> Self {
+        Self { frames }
+    }
+}
+
+/// The events between two checkpoints.
+struct Step {
+    kinds: Vec<EventKind>,
+    key_frame: bool,
+}
+
+/// Turns an [`EventLog`] into a [`FrameSchedule`], so steps get screen time by what happens in
+/// them instead of one frame each.
+pub struct Timeline<'a, R> {
+    base_duration: usize,
+    durations: Vec<(EventKind, usize)>,
+    key_frame: Option<KeyFrameFn<'a, R>>,
+    hold: usize,
+    fast_forward: Option<(usize, usize)>,
+}
+
+impl<'a, R> Timeline<'a, R> {
+    pub fn create() -> Self {
+        Self {
+            base_duration: 1,
+            durations: Vec::new(),
+            key_frame: None,
+            hold: 0,
+            fast_forward: None,
+        }
+    }
+
+    /// Frames every step is shown for at least.
+    pub fn base_duration(mut self, frames: usize) -> Self {
+        self.base_duration = frames;
+        self
+    }
+
+    /// Frames a step containing an event of `kind` is shown for; a step lasts as long as its
+    /// longest event.
+    pub fn duration(mut self, kind: EventKind, frames: usize) -> Self {
+        self.durations.retain(|(k, _)| *k != kind);
+        self.durations.push((kind, frames));
+        self
+    }
+
+    /// Steps with an event matching `is_key_frame` are held for `frames` frames and never
+    /// fast forwarded.
+    pub fn key_frames<F>(mut self, frames: usize, is_key_frame: F) -> Self
+    where
+        F: Fn(&Event<R>) -> bool + 'a,
+    {
+        self.hold = frames;
+        self.key_frame = Some(Box::new(is_key_frame));
+        self
+    }
+
+    /// After `after` similar steps in a row, i.e. steps with the same kinds of events, only
+    /// every `every`th one of the following similar steps is shown.
+    pub fn fast_forward(mut self, after: usize, every: usize) -> Self {
+        self.fast_forward = Some((after, every.max(1)));
+        self
+    }
+
+    fn steps(&self, log: &EventLog<R>) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let mut current = Step {
+            kinds: Vec::new(),
+            key_frame: false,
+        };
+        for event in log.events() {
+            match EventKind::of(event) {
+                Some(kind) => {
+                    if !current.kinds.contains(&kind) {
+                        current.kinds.push(kind);
+                    }
+                    if let Some(is_key_frame) = self.key_frame.as_ref() {
+                        current.key_frame |= is_key_frame(event);
+                    }
+                }
+                None => {
+                    current.kinds.sort();
+                    steps.push(current);
+                    current = Step {
+                        kinds: Vec::new(),
+                        key_frame: false,
+                    };
+                }
+            }
+        }
+        steps
+    }
+
+    fn duration_of(&self, step: &Step) -> usize {
+        let duration = self
+            .durations
+            .iter()
+            .filter(|(kind, _)| step.kinds.contains(kind))
+            .map(|(_, frames)| *frames)
+            .fold(self.base_duration, usize::max);
+        match step.key_frame {
+            true => duration.max(self.hold),
+            false => duration,
+        }
+    }
+
+    /// Frames for every checkpoint in `log`. The last checkpoint always gets at least one
+    /// frame so the final state is shown.
+    pub fn schedule(&self, log: &EventLog<R>) -> FrameSchedule {
+        let steps = self.steps(log);
+        let mut frames = Vec::with_capacity(steps.len());
+        let mut run = 0;
+        for (index, step) in steps.iter().enumerate() {
+            let similar = index > 0 && !step.key_frame && {
+                let previous = &steps[index - 1];
+                !previous.key_frame && previous.kinds == step.kinds
+            };
+            run = match similar {
+                true => run + 1,
+                false => 0,
+            };
+            let duration = match self.fast_forward {
+                Some((after, every)) if run >= after && (run - after + 1) % every != 0 => 0,
+                _ => self.duration_of(step),
+            };
+            frames.push(duration);
+        }
+        if let Some(last) = frames.last_mut() {
+            *last = (*last).max(1);
+        }
+        FrameSchedule::from(frames)
+    }
+}
+
+impl<'a, R> Default for Timeline<'a, R> {
+    fn default() -> Self {
+        Self::create()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::draw::timeline::{EventKind, FrameSchedule, Timeline};
+    use crate::position::Position;
+    use crate::visualize::{Event, EventLog, Visualizer};
+
+    fn log(steps: &[&[EventKind]]) -> EventLog<bool> {
+        let mut log = EventLog::new();
+        for kinds in steps {
+            for kind in kinds.iter() {
+                match kind {
+                    EventKind::Highlight => log.highlight(Position::new(0, 0), false),
+                    EventKind::Focus => log.focus(Position::new(0, 0)),
+                    EventKind::Counter => log.counter("sum", 1),
+                    EventKind::Visit => log.visit(Position::new(0, 0)),
+                }
+                .unwrap();
+            }
+            log.checkpoint().unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn test_default_is_one_frame_per_checkpoint() {
+        let log = log(&[&[EventKind::Visit], &[], &[EventKind::Focus]]);
+        let schedule = Timeline::create().schedule(&log);
+        assert_eq!(schedule, FrameSchedule::from(vec![1, 1, 1]));
+        assert_eq!(schedule.frames(10), 1);
+    }
+
+    #[test]
+    fn test_steps_last_as_long_as_their_longest_event() {
+        let log = log(&[
+            &[EventKind::Visit],
+            &[EventKind::Visit, EventKind::Highlight],
+            &[EventKind::Focus],
+        ]);
+        let schedule = Timeline::create()
+            .duration(EventKind::Visit, 0)
+            .duration(EventKind::Highlight, 3)
+            .duration(EventKind::Highlight, 2)
+            .base_duration(0)
+            .schedule(&log);
+        assert_eq!(schedule, FrameSchedule::from(vec![0, 2, 1]));
+        assert_eq!(schedule.total_frames(), 3);
+    }
+
+    #[test]
+    fn test_key_frames_are_held() {
+        let mut log = log(&[&[EventKind::Visit]]);
+        log.highlight(Position::new(1, 1), true).unwrap();
+        log.checkpoint().unwrap();
+        let schedule = Timeline::create()
+            .key_frames(5, |event| {
+                matches!(event, Event::Highlight { role: true, .. })
+            })
+            .schedule(&log);
+        assert_eq!(schedule, FrameSchedule::from(vec![1, 5]));
+    }
+
+    #[test]
+    fn test_fast_forward_through_similar_steps() {
+        let mut steps: Vec<&[EventKind]> = vec![&[EventKind::Visit]; 8];
+        steps.push(&[EventKind::Highlight]);
+        steps.push(&[EventKind::Visit]);
+        let schedule = Timeline::create().fast_forward(2, 3).schedule(&log(&steps));
+        assert_eq!(
+            schedule,
+            FrameSchedule::from(vec![1, 1, 0, 0, 1, 0, 0, 1, 1, 1])
+        );
+    }
+
+    #[test]
+    fn test_last_checkpoint_is_always_shown() {
+        let log = log(&[&[EventKind::Visit], &[EventKind::Visit]]);
+        let schedule = Timeline::create()
+            .duration(EventKind::Visit, 0)
+            .base_duration(0)
+            .schedule(&log);
+        assert_eq!(schedule, FrameSchedule::from(vec![0, 1]));
+    }
+}
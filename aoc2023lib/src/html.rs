@@ -0,0 +1,379 @@
+use std::fmt::Write as FmtWrite;
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use grid::Grid;
+
+use crate::color::{Color, Theme};
+use crate::position::Position;
+use crate::visualize::{Event, EventLog};
+
+type GlyphFn<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+type RoleColorFn<'a, R> = Box<dyn Fn(&R) -> Color + 'a>;
+
+/// What changed between two checkpoints of an event log.
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    focus: Option<Position>,
+    highlights: Vec<(Position, usize)>,
+    counters: Vec<(String, i64)>,
+}
+
+/// Writes a grid and an [`EventLog`] as a single HTML file: the grid is one embedded SVG and
+/// every checkpoint becomes a frame that can be stepped through or played in the browser.
+pub struct HtmlExporter<'a, T, R> {
+    glyph: GlyphFn<'a, T>,
+    role_color: RoleColorFn<'a, R>,
+    title: String,
+    cell_size: usize,
+    frame_delay_ms: u32,
+    viewport: Option<(usize, usize)>,
+    background: Color,
+    base_color: Color,
+    text_color: Color,
+}
+
+impl<'a, T, R> HtmlExporter<'a, T, R> {
+    pub fn create<G, C>(glyph: G, role_color: C) -> Self
+    where
+        G: Fn(&T) -> String + 'a,
+        C: Fn(&R) -> Color + 'a,
+    {
+        let theme = Theme::light();
+        Self {
+            glyph: Box::new(glyph),
+            role_color: Box::new(role_color),
+            title: "Visualization".to_string(),
+            cell_size: 20,
+            frame_delay_ms: 100,
+            viewport: None,
+            background: theme.background,
+            base_color: theme.cell_background,
+            text_color: theme.text,
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    /// Time between frames while playing.
+    pub fn frame_delay(mut self, frame_delay_ms: u32) -> Self {
+        self.frame_delay_ms = frame_delay_ms;
+        self
+    }
+
+    /// Only show `cols` x `rows` cells at a time, following the focus.
+    pub fn viewport(mut self, cols: usize, rows: usize) -> Self {
+        self.viewport = Some((cols.max(1), rows.max(1)));
+        self
+    }
+
+    pub fn theme(mut self, theme: &Theme) -> Self {
+        self.background = theme.background;
+        self.base_color = theme.cell_background;
+        self.text_color = theme.text;
+        self
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, grid: &Grid<T>, log: &EventLog<R>, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut file =
+            File::create(path).with_context(|| format!("Could not create {:?}", path))?;
+        file.write_all(self.render(grid, log).as_bytes())
+            .with_context(|| format!("Could not write {:?}", path))?;
+        Ok(())
+    }
+
+    pub fn render(&self, grid: &Grid<T>, log: &EventLog<R>) -> String {
+        let (colors, frames) = self.frames(grid, log);
+        let size = self.cell_size;
+        let (view_cols, view_rows) = self.viewport.unwrap_or((grid.cols(), grid.rows()));
+        let (view_cols, view_rows) = (view_cols.min(grid.cols()), view_rows.min(grid.rows()));
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg id="grid" width="{}" height="{}" viewBox="0 0 {} {}" font-size="{}">"#,
+            view_cols * size,
+            view_rows * size,
+            view_cols * size,
+            view_rows * size,
+            size as f64 * 0.8,
+        );
+        let _ = writeln!(svg, r#"<g fill="{}">"#, self.base_color.to_hex());
+        for position in Position::iter_grid(grid) {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                position.x() * size,
+                position.y() * size,
+                size,
+                size
+            );
+        }
+        let _ = writeln!(svg, r#"</g><g fill="{}">"#, self.text_color.to_hex());
+        for position in Position::iter_grid(grid) {
+            let glyph = position
+                .grid_value(grid)
+                .map(|value| (self.glyph)(value))
+                .unwrap_or_default();
+            if !glyph.is_empty() {
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}">{}</text>"#,
+                    position.x() * size + size / 2,
+                    position.y() * size + size / 2,
+                    escape_html(glyph.as_str())
+                );
+            }
+        }
+        svg.push_str("</g></svg>");
+
+        let config = format!(
+            r#"{{"cols":{},"rows":{},"cellSize":{},"viewCols":{},"viewRows":{},"delay":{},"base":"{}"}}"#,
+            grid.cols(),
+            grid.rows(),
+            size,
+            view_cols,
+            view_rows,
+            self.frame_delay_ms,
+            self.base_color.to_hex()
+        );
+        let colors = format!(
+            "[{}]",
+            colors
+                .iter()
+                .map(|color| format!("\"{}\"", color.to_hex()))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        fill_template(
+            include_str!("html/viewer.html"),
+            &[
+                ("__TITLE__", escape_html(self.title.as_str()).as_str()),
+                ("__BACKGROUND__", self.background.to_hex().as_str()),
+                ("__SVG__", svg.as_str()),
+                ("__CONFIG__", config.as_str()),
+                ("__COLORS__", colors.as_str()),
+                ("__FRAMES__", frames_json(&frames).as_str()),
+            ],
+        )
+    }
+
+    /// Colors used by highlights, and one frame per checkpoint referring to them by index.
+    /// Highlights outside `grid`, e.g. from a replayed log of another input, are dropped.
+    fn frames(&self, grid: &Grid<T>, log: &EventLog<R>) -> (Vec<Color>, Vec<Frame>) {
+        let mut colors: Vec<Color> = Vec::new();
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut current = Frame {
+            focus: None,
+            highlights: Vec::new(),
+            counters: Vec::new(),
+        };
+        for event in log.events() {
+            match event {
+                Event::Highlight { position, .. } if position.grid_value(grid).is_none() => {}
+                Event::Highlight { position, role } => {
+                    let color = (self.role_color)(role).blend_over(&self.base_color);
+                    let index = match colors.iter().position(|c| *c == color) {
+                        Some(index) => index,
+                        None => {
+                            colors.push(color);
+                            colors.len() - 1
+                        }
+                    };
+                    current.highlights.push((*position, index));
+                }
+                Event::Focus(position) => current.focus = Some(*position),
+                Event::Counter { name, value } => {
+                    match current.counters.iter_mut().find(|(n, _)| n == name) {
+                        Some((_, current)) => *current = *value,
+                        None => current.counters.push((name.clone(), *value)),
+                    }
+                }
+                Event::Checkpoint => {
+                    frames.push(current.clone());
+                    current.highlights.clear();
+                }
+                Event::Visit(_) => {}
+            }
+        }
+        (colors, frames)
+    }
+}
+
+/// Frames as `[[focus x, focus y], [[x, y, color], ...], [[name, value], ...]]`, with
+/// counters only listed when they changed.
+fn frames_json(frames: &[Frame]) -> String {
+    let mut json = String::from("[");
+    let mut previous_counters: &[(String, i64)] = &[];
+    for (index, frame) in frames.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        let focus = match frame.focus {
+            Some(position) => format!("[{},{}]", position.x(), position.y()),
+            None => "null".to_string(),
+        };
+        let highlights = frame
+            .highlights
+            .iter()
+            .map(|(position, color)| format!("[{},{},{}]", position.x(), position.y(), color))
+            .collect::<Vec<_>>()
+            .join(",");
+        let counters = match frame.counters.as_slice() == previous_counters {
+            true => "0".to_string(),
+            false => format!(
+                "[{}]",
+                frame
+                    .counters
+                    .iter()
+                    .map(|(name, value)| format!("[{},{}]", escape_json(name), value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+        previous_counters = frame.counters.as_slice();
+        let _ = write!(json, "[{},[{}],{}]", focus, highlights, counters);
+    }
+    json.push(']');
+    json
+}
+
+/// Replaces the placeholders in `template` in a single pass, so values are never searched for
+/// placeholders themselves.
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some((index, key, value)) = values
+        .iter()
+        .filter_map(|(key, value)| rest.find(key).map(|index| (index, *key, *value)))
+        .min_by_key(|(index, _, _)| *index)
+    {
+        output.push_str(&rest[..index]);
+        output.push_str(value);
+        rest = &rest[index + key.len()..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A JSON string literal that is also safe inside a `<script>` element.
+fn escape_json(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '<' => json.push_str("\\u003c"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod test {
+    use grid::Grid;
+
+    use crate::color::Color;
+    use crate::html::{escape_json, fill_template, HtmlExporter};
+    use crate::position::Position;
+    use crate::visualize::{EventLog, Visualizer};
+
+    fn exporter<'a>() -> HtmlExporter<'a, char, bool> {
+        HtmlExporter::create(
+            |c: &char| match c {
+                '.' => String::new(),
+                c => c.to_string(),
+            },
+            |important: &bool| match important {
+                true => Color::RED,
+                false => Color::BLUE,
+            },
+        )
+    }
+
+    fn log() -> EventLog<bool> {
+        let mut log = EventLog::new();
+        log.focus(Position::new(1, 0)).unwrap();
+        log.highlight(Position::new(1, 0), true).unwrap();
+        log.counter("sum", 4).unwrap();
+        log.checkpoint().unwrap();
+        log.highlight(Position::new(0, 1), false).unwrap();
+        log.checkpoint().unwrap();
+        log
+    }
+
+    fn grid() -> Grid<char> {
+        Grid::from_vec("a.<.".chars().collect(), 2)
+    }
+
+    #[test]
+    fn test_frames_per_checkpoint() {
+        let (colors, frames) = exporter().frames(&grid(), &log());
+        assert_eq!(colors, vec![Color::RED, Color::BLUE]);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].highlights, vec![(Position::new(1, 0), 0)]);
+        assert_eq!(frames[1].highlights, vec![(Position::new(0, 1), 1)]);
+        assert_eq!(frames[1].focus, Some(Position::new(1, 0)));
+    }
+
+    #[test]
+    fn test_frames_drop_highlights_outside_grid() {
+        let mut log = log();
+        log.highlight(Position::new(2, 0), true).unwrap();
+        log.highlight(Position::new(1, 1), true).unwrap();
+        log.checkpoint().unwrap();
+        let (_, frames) = exporter().frames(&grid(), &log);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].highlights, vec![(Position::new(1, 1), 0)]);
+    }
+
+    #[test]
+    fn test_render_embeds_grid_and_frames() {
+        let html = exporter().title("a & b").render(&grid(), &log());
+        assert!(html.contains("<title>a &amp; b</title>"));
+        assert_eq!(html.matches("<rect ").count(), 4);
+        assert_eq!(html.matches("<text ").count(), 2);
+        assert!(html.contains(">&lt;</text>"));
+        assert!(html.contains(r#"[[[1,0],[[1,0,0]],[["sum",4]]],[[1,0],[[0,1,1]],0]]"#));
+    }
+
+    #[test]
+    fn test_fill_template_does_not_expand_values() {
+        let filled = fill_template("<__A__|__B__>", &[("__A__", "__B__"), ("__B__", "b")]);
+        assert_eq!(filled, "<__B__|b>");
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(
+            escape_json("a\"b</script>\n"),
+            r#""a\"b\u003c/script>\u000a""#
+        );
+    }
+}
@@ -1,5 +1,11 @@
+pub mod color;
 #[cfg(feature = "draw")]
 pub mod draw;
+pub mod html;
+pub mod position;
+pub mod span;
+pub mod terminal;
+pub mod visualize;
 
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
@@ -0,0 +1,43 @@
+use std::ops::Add;
+
+use grid::Grid;
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Position {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    pub fn grid_value<'a, T>(&self, grid: &'a Grid<T>) -> Option<&'a T> {
+        grid.get(self.y, self.x)
+    }
+
+    pub fn iter_grid<T>(grid: &Grid<T>) -> impl Iterator<Item = Position> {
+        let cols = grid.cols();
+        (0..grid.rows()).flat_map(move |y| (0..cols).map(move |x| Position::new(x, y)))
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Position {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
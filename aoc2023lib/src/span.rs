@@ -0,0 +1,381 @@
+use std::collections::Bound;
+use std::ops::{Range, RangeBounds};
+
+use crate::color::Color;
+use crate::terminal::{ColorMode, RESET};
+
+const LEFT_BOTTOM_CORNER: &str = "└";
+const RIGHT_BOTTOM_CORNER: &str = "┘";
+const HORIZONTAL: &str = "─";
+const ARROW_UP: &str = "↑";
+
+/// Start and end of `range` as char indices into a text of `len` chars, both clamped to `len`.
+/// A reversed range keeps its start after its end.
+fn char_span<R: RangeBounds<usize>>(range: &R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&x) => x,
+        Bound::Excluded(&x) => x.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&x) => x.saturating_add(1),
+        Bound::Excluded(&x) => x,
+        Bound::Unbounded => len,
+    };
+    (start.min(len), end.min(len))
+}
+
+/// `text` with `[` and `]` around the chars in `range`. Ranges are in chars and clamped to the
+/// text; a reversed range is shown with its brackets swapped, e.g. `]01234[`.
+pub fn format_text_span<R>(text: &str, range: R) -> String
+where
+    R: RangeBounds<usize>,
+{
+    let chars: Vec<char> = text.chars().collect();
+    let (start, end) = char_span(&range, chars.len());
+    let (left, right, open, close) = match start <= end {
+        true => (start, end, '[', ']'),
+        false => (end, start, ']', '['),
+    };
+
+    let mut formatted = String::with_capacity(text.len() + 2);
+    formatted.extend(&chars[..left]);
+    formatted.push(open);
+    formatted.extend(&chars[left..right]);
+    formatted.push(close);
+    formatted.extend(&chars[right..]);
+    formatted
+}
+
+/// The marker below a span of `size` chars: an arrow below a single char, a bracket below
+/// longer spans.
+fn marker(size: usize) -> String {
+    match size {
+        0 => "".to_string(),
+        1 => ARROW_UP.to_string(),
+        size => format!(
+            "{}{}{}",
+            LEFT_BOTTOM_CORNER,
+            HORIZONTAL.repeat(size - 2),
+            RIGHT_BOTTOM_CORNER,
+        ),
+    }
+}
+
+/// Orders a possibly reversed range.
+fn ordered(range: Range<usize>) -> Range<usize> {
+    match range.start <= range.end {
+        true => range,
+        false => range.end..range.start,
+    }
+}
+
+/// A line marking the chars in `range`, one column per char. Reversed ranges mark the same
+/// chars as their ordered version.
+pub fn format_span_marker(range: Range<usize>) -> String {
+    let range = ordered(range);
+    format!("{}{}", " ".repeat(range.start), marker(range.len()))
+}
+
+/// `text` with the chars in `range` marked on the line below, see [`format_span_marker`]. The
+/// range is clamped to the text.
+pub fn format_text_with_marked_span_multiline(text: &str, range: Range<usize>) -> String {
+    let len = text.chars().count();
+    let range = range.start.min(len)..range.end.min(len);
+    format!("{}\n{}", text, format_span_marker(range))
+}
+
+/// A range of chars in a source, with an optional label shown next to its marker and a color
+/// for the marker, label and marked chars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    range: Range<usize>,
+    label: Option<String>,
+    color: Option<Color>,
+}
+
+impl Span {
+    /// Reversed ranges are turned around.
+    pub fn new(range: Range<usize>) -> Self {
+        Self {
+            range: ordered(range),
+            label: None,
+            color: None,
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn range(&self) -> &Range<usize> {
+        &self.range
+    }
+}
+
+/// The part of a span on one line of the source, in columns of that line.
+struct Segment<'a> {
+    columns: Range<usize>,
+    label: Option<&'a str>,
+    color: Option<Color>,
+}
+
+impl<'a> Segment<'a> {
+    fn width(&self) -> usize {
+        let label = self.label.map_or(0, |label| label.chars().count() + 1);
+        self.columns.start + self.columns.len() + label
+    }
+}
+
+/// Renders a source with any number of [`Span`]s marked below the lines they cover. Spans are
+/// in chars of the whole source, newlines included, so they can cover several lines; the label
+/// goes on the last one. Spans that don't overlap share a row of markers.
+#[derive(Debug, Clone)]
+pub struct SpanRenderer {
+    spans: Vec<Span>,
+    color_mode: ColorMode,
+}
+
+impl SpanRenderer {
+    pub fn create() -> Self {
+        Self {
+            spans: Vec::new(),
+            color_mode: ColorMode::Plain,
+        }
+    }
+
+    pub fn span(mut self, span: Span) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    pub fn spans<I: IntoIterator<Item = Span>>(mut self, spans: I) -> Self {
+        self.spans.extend(spans);
+        self
+    }
+
+    pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    pub fn render(&self, source: &str) -> String {
+        let mut rendered: Vec<String> = Vec::new();
+        let mut line_start = 0;
+        for line in source.split('\n') {
+            let chars: Vec<char> = line.chars().collect();
+            let segments = self.segments(line_start, chars.len());
+            rendered.push(self.render_line(&chars, &segments));
+            rendered.extend(self.render_markers(&segments));
+            line_start += chars.len() + 1;
+        }
+        rendered.join("\n")
+    }
+
+    /// The spans on the line starting at char `line_start` of the source, `len` chars long.
+    fn segments(&self, line_start: usize, len: usize) -> Vec<Segment<'_>> {
+        let line_end = line_start + len;
+        self.spans
+            .iter()
+            .filter_map(|span| {
+                let Range { start, end } = span.range;
+                let on_line = match start == end {
+                    true => (line_start..=line_end).contains(&start),
+                    false => start < line_end && end > line_start,
+                };
+                if !on_line {
+                    return None;
+                }
+                let columns = start.max(line_start) - line_start..end.min(line_end) - line_start;
+                let is_last_line = end <= line_end + 1;
+                Some(Segment {
+                    columns,
+                    label: span.label.as_deref().filter(|_| is_last_line),
+                    color: span.color,
+                })
+            })
+            .collect()
+    }
+
+    fn colored(&self, text: &str, color: Option<Color>) -> String {
+        match (self.color_mode, color) {
+            (ColorMode::Plain, _) | (_, None) => text.to_string(),
+            (mode, Some(color)) => format!("{}{}{}", mode.escape(color, false), text, RESET),
+        }
+    }
+
+    /// The line itself, with chars in colored spans in the color of the first one.
+    fn render_line(&self, chars: &[char], segments: &[Segment]) -> String {
+        let color_at = |column: usize| {
+            segments
+                .iter()
+                .find(|segment| segment.columns.contains(&column))
+                .and_then(|segment| segment.color)
+        };
+        let mut rendered = String::new();
+        let mut run_start = 0;
+        for column in 1..=chars.len() {
+            if column == chars.len() || color_at(column) != color_at(run_start) {
+                let run: String = chars[run_start..column].iter().collect();
+                rendered.push_str(&self.colored(run.as_str(), color_at(run_start)));
+                run_start = column;
+            }
+        }
+        rendered
+    }
+
+    /// Rows of markers, each segment in the first row it fits in after the ones before it.
+    fn render_markers(&self, segments: &[Segment]) -> Vec<String> {
+        let mut rows: Vec<(String, usize)> = Vec::new();
+        for segment in segments {
+            let start = segment.columns.start;
+            let index = match rows.iter().position(|(_, width)| *width < start) {
+                Some(index) => index,
+                None => {
+                    rows.push((String::new(), 0));
+                    rows.len() - 1
+                }
+            };
+            let (row, width) = &mut rows[index];
+            row.push_str(" ".repeat(start - *width).as_str());
+            let label = segment
+                .label
+                .map(|label| format!(" {}", label))
+                .unwrap_or_default();
+            let marker = format!("{}{}", marker(segment.columns.len()), label);
+            row.push_str(&self.colored(marker.as_str(), segment.color));
+            *width = segment.width();
+        }
+        rows.into_iter().map(|(row, _)| row).collect()
+    }
+}
+
+impl Default for SpanRenderer {
+    fn default() -> Self {
+        Self::create()
+    }
+}
+
+#[cfg(test)]
+// Reversed ranges are part of what is tested.
+#[allow(clippy::reversed_empty_ranges)]
+mod test {
+    use paste::paste;
+
+    use crate::color::Color;
+    use crate::span::{
+        format_span_marker, format_text_span, format_text_with_marked_span_multiline, Span,
+        SpanRenderer,
+    };
+    use crate::terminal::ColorMode;
+
+    macro_rules! test_format_text_span {
+        ($($name:ident: $value:expr,)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<test_format_text_span_ $name>]() {
+                        let (text, range, expected) = $value;
+                        assert_eq!(expected, format_text_span(text, range));
+                    }
+                }
+            )*
+        }
+    }
+
+    test_format_text_span! {
+        empty: ("01234", 0..0, "[]01234"),
+        len1: ("01234", 0..1, "[0]1234"),
+        len2: ("01234", 0..2, "[01]234"),
+        len5: ("01234", 0..5, "[01234]"),
+        empty_3: ("01234", 3..3, "012[]34"),
+        end_empty: ("01234", 5..5, "01234[]"),
+        reversed_len5: ("01234", 5..0, "]01234["),
+        reversed_len2: ("01234", 3..1, "0]12[34"),
+        // should be clamped to max
+        verify_clamped: ("01234", 0..7, "[01234]"),
+        verify_clamped_reverse: ("01234", 7..0, "]01234["),
+        clamped_empty: ("01234", 6..9, "01234[]"),
+        inclusive: ("01234", 1..=2, "0[12]34"),
+        inclusive_end: ("01234", 4..=4, "0123[4]"),
+        unbounded: ("01234", 2.., "01[234]"),
+        non_ascii: ("äöü½", 1..3, "ä[öü]½"),
+        non_ascii_inclusive: ("zwölf7", 2..=2, "zw[ö]lf7"),
+        non_ascii_clamped: ("ñ1", 1..10, "ñ[1]"),
+    }
+
+    #[test]
+    fn test_marker_is_as_wide_as_span() {
+        assert_eq!(
+            format_text_with_marked_span_multiline("a1two", 2..5),
+            "a1two\n  └─┘"
+        );
+        assert_eq!(
+            format_text_with_marked_span_multiline("a1two", 1..2),
+            "a1two\n ↑"
+        );
+        assert_eq!(
+            format_text_with_marked_span_multiline("a1two", 0..2),
+            "a1two\n└┘"
+        );
+    }
+
+    #[test]
+    fn test_marker_counts_chars() {
+        assert_eq!(
+            format_text_with_marked_span_multiline("äöone", 2..5),
+            "äöone\n  └─┘"
+        );
+    }
+
+    #[test]
+    fn test_marker_clamps_and_orders_range() {
+        assert_eq!(
+            format_text_with_marked_span_multiline("ab1", 2..9),
+            "ab1\n  ↑"
+        );
+        assert_eq!(format_span_marker(4..1), " └─┘");
+        assert_eq!(format_span_marker(2..2), "  ");
+    }
+
+    #[test]
+    fn test_render_labeled_spans_share_rows() {
+        let rendered = SpanRenderer::create()
+            .span(Span::new(0..3).label("2"))
+            .span(Span::new(2..5).label("1"))
+            .span(Span::new(6..10).label("9"))
+            .render("twone7nine");
+        assert_eq!(rendered, "twone7nine\n└─┘ 2 └──┘ 9\n  └─┘ 1");
+    }
+
+    #[test]
+    fn test_render_multi_line_source() {
+        let rendered = SpanRenderer::create()
+            .span(Span::new(1..2).label("b"))
+            .span(Span::new(2..6).label("across"))
+            .span(Span::new(20..30))
+            .render("abc\ndef");
+        assert_eq!(rendered, "abc\n ↑ b\n  ↑\ndef\n└┘ across");
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let red = Color::rgb(1., 0., 0.);
+        let rendered = SpanRenderer::create()
+            .color_mode(ColorMode::TrueColor)
+            .span(Span::new(1..3).label("x").color(red))
+            .render("abcd");
+        let escape = "\x1b[38;2;255;0;0m";
+        let reset = "\x1b[0m";
+        assert_eq!(
+            rendered,
+            format!("a{}bc{}d\n {}└┘ x{}", escape, reset, escape, reset)
+        );
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+
+use anyhow::Result;
+use grid::Grid;
+
+use crate::color::{Color, Theme};
+use crate::position::Position;
+use crate::visualize::Visualizer;
+
+pub(crate) const RESET: &str = "\x1b[0m";
+const CLEAR_SCREEN: &[u8] = b"\x1b[H\x1b[2J";
+
+/// The marker of highlights that weren't given one, see [`TerminalGridRenderer::mark`].
+pub const DEFAULT_MARKER: (char, char) = ('[', ']');
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ColorMode {
+    /// Glyphs only, no escape codes.
+    Plain,
+    /// The 6x6x6 color cube of 256 color terminals.
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorMode {
+    /// `Plain` if stdout is not a terminal or `$NO_COLOR` is set, `TrueColor` if `$COLORTERM`
+    /// says so, `Ansi256` otherwise.
+    pub fn detect() -> Self {
+        Self::detect_from(
+            std::io::stdout().is_terminal(),
+            std::env::var_os("NO_COLOR").is_some(),
+            std::env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    fn detect_from(is_terminal: bool, no_color: bool, colorterm: Option<&str>) -> Self {
+        if !is_terminal || no_color {
+            return ColorMode::Plain;
+        }
+        match colorterm {
+            Some("truecolor") | Some("24bit") => ColorMode::TrueColor,
+            _ => ColorMode::Ansi256,
+        }
+    }
+
+    pub(crate) fn escape(&self, color: Color, background: bool) -> String {
+        let layer = match background {
+            true => 48,
+            false => 38,
+        };
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self {
+            ColorMode::Plain => String::new(),
+            ColorMode::Ansi256 => format!("\x1b[{};5;{}m", layer, ansi256_index(color)),
+            ColorMode::TrueColor => format!(
+                "\x1b[{};2;{};{};{}m",
+                layer,
+                channel(color.r()),
+                channel(color.g()),
+                channel(color.b())
+            ),
+        }
+    }
+}
+
+pub fn ansi256_index(color: Color) -> u8 {
+    let level = |c: f64| (c.clamp(0.0, 1.0) * 5.0).round() as u8;
+    16 + 36 * level(color.r()) + 6 * level(color.g()) + level(color.b())
+}
+
+type GlyphFn<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+type BackgroundFn<'a, T> = Box<dyn Fn(Position, &T) -> Option<Color> + 'a>;
+
+/// Renders a [`Grid`] as lines of text with ANSI colors, the terminal counterpart of
+/// `draw::grid::GridRenderer`. Without colors highlighted cells show a marker instead: the
+/// glyph between the marker's two chars in cells at least 3 wide, the marker's first char in
+/// narrower ones.
+pub struct TerminalGridRenderer<'a, T> {
+    glyph: GlyphFn<'a, T>,
+    background: BackgroundFn<'a, T>,
+    cell_width: usize,
+    color_mode: ColorMode,
+    base_color: Color,
+    text_color: Color,
+    highlights: HashMap<Position, (Color, (char, char))>,
+}
+
+impl<'a, T> TerminalGridRenderer<'a, T> {
+    pub fn create<G>(glyph: G) -> Self
+    where
+        G: Fn(&T) -> String + 'a,
+    {
+        Self {
+            glyph: Box::new(glyph),
+            background: Box::new(|_, _| None),
+            cell_width: 1,
+            color_mode: ColorMode::TrueColor,
+            base_color: Color::WHITE,
+            text_color: Color::BLACK,
+            highlights: HashMap::new(),
+        }
+    }
+
+    pub fn background<B>(mut self, background: B) -> Self
+    where
+        B: Fn(Position, &T) -> Option<Color> + 'a,
+    {
+        self.background = Box::new(background);
+        self
+    }
+
+    /// Number of terminal columns per grid cell; glyphs are centered and cut to fit.
+    pub fn cell_width(mut self, cell_width: usize) -> Self {
+        self.cell_width = cell_width.max(1);
+        self
+    }
+
+    pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    pub fn theme(mut self, theme: &Theme) -> Self {
+        self.base_color = theme.cell_background;
+        self.text_color = theme.text;
+        self
+    }
+
+    /// Gives `position` a background that wins over the background mapper until cleared.
+    pub fn highlight(&mut self, position: Position, color: Color) {
+        self.mark(position, color, DEFAULT_MARKER);
+    }
+
+    /// Like [`TerminalGridRenderer::highlight`], with `marker` shown in place of `color`
+    /// without colors.
+    pub fn mark(&mut self, position: Position, color: Color, marker: (char, char)) {
+        self.highlights.insert(position, (color, marker));
+    }
+
+    pub fn clear_highlight(&mut self, position: Position) {
+        self.highlights.remove(&position);
+    }
+
+    pub fn clear_highlights(&mut self) {
+        self.highlights.clear();
+    }
+
+    pub fn render(&self, grid: &Grid<T>) -> String {
+        self.render_region(grid, Position::new(0, 0), grid.cols(), grid.rows())
+    }
+
+    /// Renders the `cols` x `rows` cells starting at `top_left`, clipped to the grid.
+    pub fn render_region(
+        &self,
+        grid: &Grid<T>,
+        top_left: Position,
+        cols: usize,
+        rows: usize,
+    ) -> String {
+        let x_end = (top_left.x() + cols).min(grid.cols());
+        let y_end = (top_left.y() + rows).min(grid.rows());
+        let mut output = String::new();
+        for y in top_left.y()..y_end {
+            for x in top_left.x()..x_end {
+                output.push_str(self.render_cell(grid, Position::new(x, y)).as_str());
+            }
+            if self.color_mode != ColorMode::Plain {
+                output.push_str(RESET);
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn write_to<W: Write>(&self, grid: &Grid<T>, writer: &mut W) -> Result<()> {
+        writer.write_all(self.render(grid).as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn render_cell(&self, grid: &Grid<T>, position: Position) -> String {
+        let Some(value) = position.grid_value(grid) else {
+            return " ".repeat(self.cell_width);
+        };
+        let glyph = (self.glyph)(value);
+        let highlight = self.highlights.get(&position);
+        if self.color_mode == ColorMode::Plain {
+            return match (highlight, self.cell_width) {
+                (Some((_, (open, close))), width @ 3..) => {
+                    format!("{}{}{}", open, fit(&glyph, width - 2), close)
+                }
+                (Some((_, (open, _))), width) => fit(open.to_string().as_str(), width),
+                (None, width) => fit(&glyph, width),
+            };
+        }
+        let background = highlight
+            .map(|(color, _)| *color)
+            .or_else(|| (self.background)(position, value))
+            .map(|c| c.blend_over(&self.base_color))
+            .unwrap_or(self.base_color);
+        format!(
+            "{}{}{}",
+            self.color_mode.escape(background, true),
+            self.color_mode.escape(self.text_color, false),
+            fit(&glyph, self.cell_width)
+        )
+    }
+}
+
+/// `glyph` cut to `width` chars and centered in them.
+fn fit(glyph: &str, width: usize) -> String {
+    let glyph: String = glyph.chars().take(width).collect();
+    format!("{:^width$}", glyph, width = width)
+}
+
+type RoleColorFn<'a, R> = Box<dyn Fn(&R) -> Color + 'a>;
+type RoleMarkerFn<'a, R> = Box<dyn Fn(&R) -> (char, char) + 'a>;
+
+/// A [`Visualizer`] that prints the grid, or a viewport around the focus, on every checkpoint.
+pub struct TerminalVisualizer<'a, T, R, W: Write> {
+    grid: Grid<T>,
+    renderer: TerminalGridRenderer<'a, T>,
+    role_color: RoleColorFn<'a, R>,
+    role_marker: Option<RoleMarkerFn<'a, R>>,
+    writer: W,
+    viewport: Option<(usize, usize)>,
+    clear: bool,
+    focus: Option<Position>,
+    counters: Vec<(String, i64)>,
+}
+
+impl<'a, T, R, W: Write> TerminalVisualizer<'a, T, R, W> {
+    pub fn create<C>(
+        grid: Grid<T>,
+        renderer: TerminalGridRenderer<'a, T>,
+        role_color: C,
+        writer: W,
+    ) -> Self
+    where
+        C: Fn(&R) -> Color + 'a,
+    {
+        Self {
+            grid,
+            renderer,
+            role_color: Box::new(role_color),
+            role_marker: None,
+            writer,
+            viewport: None,
+            clear: false,
+            focus: None,
+            counters: Vec::new(),
+        }
+    }
+
+    /// Only print `cols` x `rows` cells, centered on the focus where the grid allows.
+    pub fn viewport(mut self, cols: usize, rows: usize) -> Self {
+        self.viewport = Some((cols, rows));
+        self
+    }
+
+    /// Markers highlights of a role get without colors, [`DEFAULT_MARKER`] if not set.
+    pub fn markers<M>(mut self, role_marker: M) -> Self
+    where
+        M: Fn(&R) -> (char, char) + 'a,
+    {
+        self.role_marker = Some(Box::new(role_marker));
+        self
+    }
+
+    /// Clear the screen before each checkpoint so frames replace each other. Ignored without
+    /// colors, as that is what output that isn't a terminal gets.
+    pub fn clear(mut self) -> Self {
+        self.clear = true;
+        self
+    }
+
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn viewport_top_left(&self, cols: usize, rows: usize) -> Position {
+        let focus = self.focus.unwrap_or(Position::new(0, 0));
+        let start = |focus: usize, size: usize, total: usize| {
+            focus
+                .saturating_sub(size / 2)
+                .min(total.saturating_sub(size))
+        };
+        Position::new(
+            start(focus.x(), cols, self.grid.cols()),
+            start(focus.y(), rows, self.grid.rows()),
+        )
+    }
+}
+
+impl<'a, T, R, W: Write> Visualizer<R> for TerminalVisualizer<'a, T, R, W> {
+    fn highlight(&mut self, position: Position, role: R) -> Result<()> {
+        let marker = self
+            .role_marker
+            .as_ref()
+            .map_or(DEFAULT_MARKER, |role_marker| role_marker(&role));
+        self.renderer
+            .mark(position, (self.role_color)(&role), marker);
+        Ok(())
+    }
+
+    fn focus(&mut self, position: Position) -> Result<()> {
+        self.focus = Some(position);
+        Ok(())
+    }
+
+    fn checkpoint(&mut self) -> Result<()> {
+        let rendered = match self.viewport {
+            Some((cols, rows)) => {
+                let top_left = self.viewport_top_left(cols, rows);
+                self.renderer
+                    .render_region(&self.grid, top_left, cols, rows)
+            }
+            None => self.renderer.render(&self.grid),
+        };
+        if self.clear && self.renderer.color_mode != ColorMode::Plain {
+            self.writer.write_all(CLEAR_SCREEN)?;
+        }
+        self.writer.write_all(rendered.as_bytes())?;
+        for (name, value) in self.counters.iter() {
+            writeln!(self.writer, "{}: {}", name, value)?;
+        }
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn counter(&mut self, name: &str, value: i64) -> Result<()> {
+        match self.counters.iter_mut().find(|(n, _)| n == name) {
+            Some((_, current)) => *current = value,
+            None => self.counters.push((name.to_string(), value)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use grid::Grid;
+
+    use crate::color::Color;
+    use crate::position::Position;
+    use crate::terminal::{ansi256_index, ColorMode, TerminalGridRenderer, TerminalVisualizer};
+    use crate::visualize::Visualizer;
+
+    fn renderer<'a>() -> TerminalGridRenderer<'a, char> {
+        TerminalGridRenderer::create(|c: &char| c.to_string())
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let grid = Grid::from_vec("ab.cd.".chars().collect(), 3);
+        let rendered = renderer().color_mode(ColorMode::Plain).render(&grid);
+        assert_eq!(rendered, "ab.\ncd.\n");
+    }
+
+    #[test]
+    fn test_render_plain_wide_cells() {
+        let grid = Grid::from_vec("ab".chars().collect(), 2);
+        let rendered = renderer()
+            .color_mode(ColorMode::Plain)
+            .cell_width(3)
+            .render(&grid);
+        assert_eq!(rendered, " a  b \n");
+    }
+
+    #[test]
+    fn test_render_region_is_clipped() {
+        let grid = Grid::from_vec("abcdefghi".chars().collect(), 3);
+        let rendered =
+            renderer()
+                .color_mode(ColorMode::Plain)
+                .render_region(&grid, Position::new(1, 1), 5, 5);
+        assert_eq!(rendered, "ef\nhi\n");
+    }
+
+    #[test]
+    fn test_highlight_truecolor() {
+        let grid = Grid::from_vec("ab".chars().collect(), 2);
+        let mut renderer = renderer().color_mode(ColorMode::TrueColor);
+        renderer.highlight(Position::new(1, 0), Color::RED);
+        let rendered = renderer.render(&grid);
+        assert_eq!(
+            rendered,
+            "\x1b[48;2;255;255;255m\x1b[38;2;0;0;0ma\x1b[48;2;255;0;0m\x1b[38;2;0;0;0mb\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_detect_color_mode() {
+        assert_eq!(
+            ColorMode::detect_from(false, false, Some("truecolor")),
+            ColorMode::Plain
+        );
+        assert_eq!(
+            ColorMode::detect_from(true, true, Some("truecolor")),
+            ColorMode::Plain
+        );
+        assert_eq!(
+            ColorMode::detect_from(true, false, Some("24bit")),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::detect_from(true, false, None),
+            ColorMode::Ansi256
+        );
+    }
+
+    #[test]
+    fn test_ansi256_index() {
+        assert_eq!(ansi256_index(Color::BLACK), 16);
+        assert_eq!(ansi256_index(Color::WHITE), 231);
+        assert_eq!(ansi256_index(Color::RED), 196);
+    }
+
+    #[test]
+    fn test_visualizer_prints_viewport_around_focus() {
+        let grid = Grid::from_vec("abcdefghi".chars().collect(), 3);
+        let mut visualizer = TerminalVisualizer::create(
+            grid,
+            renderer().color_mode(ColorMode::Plain),
+            |_: &()| Color::RED,
+            Vec::new(),
+        )
+        .viewport(2, 2);
+        visualizer.checkpoint().unwrap();
+        visualizer.focus(Position::new(2, 2)).unwrap();
+        visualizer.highlight(Position::new(2, 2), ()).unwrap();
+        visualizer.counter("sum", 3).unwrap();
+        visualizer.checkpoint().unwrap();
+        let output = String::from_utf8(visualizer.into_writer()).unwrap();
+        assert_eq!(output, "ab\nde\n\nef\nh[\nsum: 3\n\n");
+    }
+
+    #[test]
+    fn test_plain_marks_highlights_by_role() {
+        let grid = Grid::from_vec("ab".chars().collect(), 2);
+        let mut visualizer = TerminalVisualizer::create(
+            grid,
+            renderer().color_mode(ColorMode::Plain).cell_width(3),
+            |_: &bool| Color::RED,
+            Vec::new(),
+        )
+        .markers(|important: &bool| match important {
+            true => ('<', '>'),
+            false => ('(', ')'),
+        })
+        .clear();
+        visualizer.highlight(Position::new(0, 0), true).unwrap();
+        visualizer.highlight(Position::new(1, 0), false).unwrap();
+        visualizer.checkpoint().unwrap();
+        let output = String::from_utf8(visualizer.into_writer()).unwrap();
+        assert_eq!(output, "<a>(b)\n\n");
+    }
+
+    #[test]
+    fn test_clear_with_colors() {
+        let grid = Grid::from_vec("a".chars().collect(), 1);
+        let mut visualizer = TerminalVisualizer::create(
+            grid,
+            renderer().color_mode(ColorMode::Ansi256),
+            |_: &()| Color::RED,
+            Vec::new(),
+        )
+        .clear();
+        visualizer.checkpoint().unwrap();
+        let output = visualizer.into_writer();
+        assert!(output.starts_with(b"\x1b[H\x1b[2J"));
+    }
+}
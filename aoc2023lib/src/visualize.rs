@@ -0,0 +1,351 @@
+use std::fmt::Display;
+use std::fs::{create_dir_all, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::position::Position;
+
+/// Receives what a solver is doing so it can be shown without the solver knowing how.
+///
+/// `R` is the solver's own set of highlight roles, e.g. "symbol" or "part number".
+pub trait Visualizer<R> {
+    /// Marks `position` as having `role` from now on.
+    fn highlight(&mut self, position: Position, role: R) -> Result<()>;
+
+    /// Moves the attention to `position`.
+    fn focus(&mut self, position: Position) -> Result<()>;
+
+    /// The state so far is worth showing, e.g. as a frame.
+    fn checkpoint(&mut self) -> Result<()>;
+
+    /// A running value such as "sum so far" changed to `value`.
+    fn counter(&mut self, _name: &str, _value: i64) -> Result<()> {
+        Ok(())
+    }
+
+    /// The solver looked at `position`, to show where it spends its effort.
+    fn visit(&mut self, _position: Position) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Ignores all events, for runs where only the answer matters.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoopVisualizer;
+
+impl<R> Visualizer<R> for NoopVisualizer {
+    fn highlight(&mut self, _position: Position, _role: R) -> Result<()> {
+        Ok(())
+    }
+
+    fn focus(&mut self, _position: Position) -> Result<()> {
+        Ok(())
+    }
+
+    fn checkpoint(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Event<R> {
+    Highlight { position: Position, role: R },
+    Focus(Position),
+    Checkpoint,
+    Counter { name: String, value: i64 },
+    Visit(Position),
+}
+
+/// Records events so they can be inspected or replayed into another [`Visualizer`] later.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EventLog<R> {
+    events: Vec<Event<R>>,
+}
+
+impl<R> EventLog<R> {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn events(&self) -> &[Event<R>] {
+        self.events.as_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn replay<V: Visualizer<R> + ?Sized>(&self, visualizer: &mut V) -> Result<()>
+    where
+        R: Clone,
+    {
+        for event in self.events.iter() {
+            match event {
+                Event::Highlight { position, role } => {
+                    visualizer.highlight(*position, role.clone())?
+                }
+                Event::Focus(position) => visualizer.focus(*position)?,
+                Event::Checkpoint => visualizer.checkpoint()?,
+                Event::Counter { name, value } => visualizer.counter(name, *value)?,
+                Event::Visit(position) => visualizer.visit(*position)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Display> EventLog<R> {
+    /// Writes one event per line: `f <x> <y>`, `h <x> <y> <role>`, `c`, `n <value> <name>` or
+    /// `v <x> <y>`. Backslashes, spaces and line breaks in roles and names are escaped as
+    /// `\\`, `\s`, `\n` and `\r`, so every field is a single token.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        for event in self.events.iter() {
+            match event {
+                Event::Highlight { position, role } => {
+                    let role = escape_field(role.to_string().as_str());
+                    writeln!(writer, "h {} {} {}", position.x(), position.y(), role)?
+                }
+                Event::Focus(position) => writeln!(writer, "f {} {}", position.x(), position.y())?,
+                Event::Checkpoint => writeln!(writer, "c")?,
+                Event::Counter { name, value } => {
+                    writeln!(writer, "n {} {}", value, escape_field(name))?
+                }
+                Event::Visit(position) => writeln!(writer, "v {} {}", position.x(), position.y())?,
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let file = File::create(path).with_context(|| format!("Could not create {:?}", path))?;
+        self.write_to(&mut BufWriter::new(file))
+    }
+}
+
+impl<R> EventLog<R>
+where
+    R: FromStr,
+    R::Err: Display,
+{
+    pub fn read_from<B: BufRead>(reader: B) -> Result<Self> {
+        let mut events: Vec<Event<R>> = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = parse_event(line.as_str())
+                .with_context(|| format!("Invalid event on line {}: {:?}", index + 1, line))?;
+            events.push(event);
+        }
+        Ok(Self { events })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+        Self::read_from(BufReader::new(file))
+    }
+}
+
+/// `field` with backslashes, spaces and line breaks escaped as `\\`, `\s`, `\n` and `\r`.
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ' ' => escaped.push_str("\\s"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_field(field: &str) -> Result<String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            other => return Err(anyhow!("Invalid escape \\{}", other.unwrap_or(' '))),
+        }
+    }
+    Ok(unescaped)
+}
+
+fn parse_event<R>(line: &str) -> Result<Event<R>>
+where
+    R: FromStr,
+    R::Err: Display,
+{
+    let mut parts = line.split(' ');
+    let kind = parts.next().unwrap_or_default();
+    let mut field = |what: &str| parts.next().ok_or_else(|| anyhow!("Missing {}", what));
+    let mut coordinate = || -> Result<usize> { Ok(field("coordinate")?.parse::<usize>()?) };
+    let event = match kind {
+        "c" => Event::Checkpoint,
+        "f" => Event::Focus(Position::new(coordinate()?, coordinate()?)),
+        "v" => Event::Visit(Position::new(coordinate()?, coordinate()?)),
+        "h" => {
+            let position = Position::new(coordinate()?, coordinate()?);
+            let role = unescape_field(field("role")?)?;
+            let role = role
+                .parse::<R>()
+                .map_err(|err| anyhow!("Invalid role {:?}: {}", role, err))?;
+            Event::Highlight { position, role }
+        }
+        "n" => {
+            let value = field("counter value")?.parse::<i64>()?;
+            let name = unescape_field(field("counter name")?)?;
+            Event::Counter { name, value }
+        }
+        other => return Err(anyhow!("Unknown event kind {:?}", other)),
+    };
+    match parts.next() {
+        Some(extra) => Err(anyhow!("Unexpected {:?} after the event", extra)),
+        None => Ok(event),
+    }
+}
+
+impl<R> Default for EventLog<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Visualizer<R> for EventLog<R> {
+    fn highlight(&mut self, position: Position, role: R) -> Result<()> {
+        self.events.push(Event::Highlight { position, role });
+        Ok(())
+    }
+
+    fn focus(&mut self, position: Position) -> Result<()> {
+        self.events.push(Event::Focus(position));
+        Ok(())
+    }
+
+    fn checkpoint(&mut self) -> Result<()> {
+        self.events.push(Event::Checkpoint);
+        Ok(())
+    }
+
+    fn counter(&mut self, name: &str, value: i64) -> Result<()> {
+        self.events.push(Event::Counter {
+            name: name.to_string(),
+            value,
+        });
+        Ok(())
+    }
+
+    fn visit(&mut self, position: Position) -> Result<()> {
+        self.events.push(Event::Visit(position));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::position::Position;
+    use crate::visualize::{Event, EventLog, Visualizer};
+
+    #[test]
+    fn test_event_log_records_in_order() {
+        let mut log: EventLog<char> = EventLog::new();
+        log.focus(Position::new(1, 2)).unwrap();
+        log.highlight(Position::new(1, 2), 'a').unwrap();
+        log.checkpoint().unwrap();
+        assert_eq!(
+            log.events(),
+            &[
+                Event::Focus(Position::new(1, 2)),
+                Event::Highlight {
+                    position: Position::new(1, 2),
+                    role: 'a'
+                },
+                Event::Checkpoint,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_round_trip() {
+        let mut log: EventLog<String> = EventLog::new();
+        log.focus(Position::new(3, 4)).unwrap();
+        log.highlight(Position::new(3, 4), "part number".to_string())
+            .unwrap();
+        log.checkpoint().unwrap();
+        log.counter("sum so far", -12).unwrap();
+        log.visit(Position::new(0, 7)).unwrap();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        log.write_to(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer.clone()).unwrap(),
+            "f 3 4\nh 3 4 part\\snumber\nc\nn -12 sum\\sso\\sfar\nv 0 7\n"
+        );
+        assert_eq!(EventLog::read_from(buffer.as_slice()).unwrap(), log);
+    }
+
+    #[test]
+    fn test_round_trip_escapes_names_and_roles() {
+        let mut log: EventLog<String> = EventLog::new();
+        log.highlight(Position::new(1, 1), "a role\nwith\\n".to_string())
+            .unwrap();
+        log.counter("sum of\r\nlines", 3).unwrap();
+        log.counter("", 0).unwrap();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        log.write_to(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer.clone()).unwrap().lines().count(),
+            3
+        );
+        assert_eq!(EventLog::read_from(buffer.as_slice()).unwrap(), log);
+    }
+
+    #[test]
+    fn test_read_reports_bad_lines() {
+        let error = EventLog::<u8>::read_from("c\nh 1 2 x\n".as_bytes()).unwrap_err();
+        assert!(format!("{:#}", error).contains("line 2"));
+        assert!(EventLog::<u8>::read_from("f 1\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("z 1 2\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("n 12\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("n\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("c 1\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("f 1 2 3\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("h 1 2 3 4\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("n 1 sum so far\n".as_bytes()).is_err());
+        assert!(EventLog::<u8>::read_from("n 1 bad\\x\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_replay_reproduces_log() {
+        let mut log: EventLog<char> = EventLog::new();
+        log.highlight(Position::new(0, 0), 'x').unwrap();
+        log.checkpoint().unwrap();
+        let mut copy = EventLog::new();
+        log.replay(&mut copy).unwrap();
+        assert_eq!(copy, log);
+    }
+}
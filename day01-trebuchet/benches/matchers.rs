@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use day01_trebuchet::find_numbers::find_numbers;
+use day01_trebuchet::matcher::DigitWordMatcher;
+
+fn input() -> Vec<String> {
+    std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .expect("Could not read input")
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn bench_matchers(c: &mut Criterion) {
+    let lines = input();
+    let mut group = c.benchmark_group("find numbers in input");
+    group.bench_function("find_numbers", |b| {
+        b.iter(|| {
+            for line in lines.iter() {
+                black_box(find_numbers(black_box(line)).unwrap());
+            }
+        })
+    });
+    group.bench_function("DigitWordMatcher", |b| {
+        let matcher = DigitWordMatcher::new();
+        b.iter(|| {
+            for line in lines.iter() {
+                black_box(matcher.find_numbers(black_box(line)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_matchers);
+criterion_main!(benches);
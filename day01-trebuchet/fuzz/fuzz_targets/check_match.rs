@@ -0,0 +1,23 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    cursor_pos: usize,
+    char_at_cursor: char,
+    start_pos: usize,
+    word: String,
+    value: u8,
+}
+
+fuzz_target!(|input: Input| {
+    day01_trebuchet::fuzzing::check_match(
+        input.cursor_pos,
+        input.char_at_cursor,
+        input.start_pos,
+        &input.word,
+        input.value,
+    );
+});
@@ -1,6 +1,8 @@
 use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone)]
+/// A digit found in a calibration line, written as a digit or as a word, with the range of
+/// chars it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CalibrationDigit {
     AsDigit {
         value: u8,
@@ -11,3 +13,23 @@ pub enum CalibrationDigit {
         range: RangeInclusive<usize>,
     },
 }
+
+impl CalibrationDigit {
+    pub fn value(&self) -> u8 {
+        match self {
+            CalibrationDigit::AsDigit { value, .. } => *value,
+            CalibrationDigit::AsWord { value, .. } => *value,
+        }
+    }
+
+    pub fn range(&self) -> &RangeInclusive<usize> {
+        match self {
+            CalibrationDigit::AsDigit { range, .. } => range,
+            CalibrationDigit::AsWord { range, .. } => range,
+        }
+    }
+
+    pub fn is_word(&self) -> bool {
+        matches!(self, CalibrationDigit::AsWord { .. })
+    }
+}
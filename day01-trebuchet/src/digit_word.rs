@@ -62,7 +62,7 @@ impl DigitWord {
     }
 
     pub(crate) fn char_vec(&self) -> Vec<char> {
-        return self.str_value().chars().collect();
+        self.str_value().chars().collect()
     }
 }
 
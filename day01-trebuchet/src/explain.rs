@@ -0,0 +1,47 @@
+use aoc2023lib::color::Color;
+use aoc2023lib::span::{Span, SpanRenderer};
+use aoc2023lib::terminal::ColorMode;
+
+use crate::calibration_digit::CalibrationDigit;
+
+/// `line` with the first and last of `digits` underlined and labeled with their values.
+pub fn explain_line(line: &str, digits: &[CalibrationDigit], color_mode: ColorMode) -> String {
+    let span = |digit: &CalibrationDigit, color: Color| {
+        Span::new(*digit.range().start()..*digit.range().end() + 1)
+            .label(digit.value().to_string().as_str())
+            .color(color)
+    };
+    let renderer = SpanRenderer::create().color_mode(color_mode);
+    match digits {
+        [] => line.to_string(),
+        [digit] => renderer.span(span(digit, Color::RED)).render(line),
+        [first, .., last] => renderer
+            .span(span(first, Color::RED))
+            .span(span(last, Color::BLUE))
+            .render(line),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aoc2023lib::terminal::ColorMode;
+
+    use crate::explain::explain_line;
+    use crate::matcher::DigitWordMatcher;
+
+    fn explain(line: &str) -> String {
+        explain_line(
+            line,
+            &DigitWordMatcher::new().find_all(line),
+            ColorMode::Plain,
+        )
+    }
+
+    #[test]
+    fn test_explain_first_and_last() {
+        assert_eq!(explain("two1nine"), "two1nine\n└─┘ 2\n    └──┘ 9");
+        assert_eq!(explain("one2345six"), "one2345six\n└─┘ 1  └─┘ 6");
+        assert_eq!(explain("ab7cd"), "ab7cd\n  ↑ 7");
+        assert_eq!(explain("abc"), "abc");
+    }
+}
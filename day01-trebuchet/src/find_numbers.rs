@@ -4,16 +4,25 @@ use anyhow::Context;
 use tracing::{instrument, trace, trace_span};
 use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
 
+use aoc2023lib::span::format_text_span;
+
 use crate::calibration_digit::CalibrationDigit;
 use crate::digit_word::DigitWord;
-use crate::utils::format_text_span;
 
 #[instrument(ret, level = "info")]
 pub fn find_numbers(text: &str) -> anyhow::Result<Vec<u8>> {
-    let mut match_start_positions: HashMap<usize, Vec<DigitWord>> =
-        create_start_positions(text.len());
-    let mut next_start_positions: HashMap<usize, Vec<DigitWord>> =
-        create_start_positions(text.len());
+    Ok(find_calibration_digits(text)?
+        .iter()
+        .map(CalibrationDigit::value)
+        .collect())
+}
+
+/// Every digit and digit word in `text` in the order they end, overlapping ones included, with
+/// their ranges in chars.
+pub fn find_calibration_digits(text: &str) -> anyhow::Result<Vec<CalibrationDigit>> {
+    let len = text.chars().count();
+    let mut match_start_positions: HashMap<usize, Vec<DigitWord>> = create_start_positions(len);
+    let mut next_start_positions: HashMap<usize, Vec<DigitWord>> = create_start_positions(len);
 
     let mut calibration_digits: Vec<CalibrationDigit> = Vec::new();
 
@@ -24,15 +33,15 @@ pub fn find_numbers(text: &str) -> anyhow::Result<Vec<u8>> {
             current = format_text_span(text, cursor_pos..=cursor_pos)
         )
         .entered();
-        match char_at_cursor.is_numeric() {
-            true => {
+        match char_at_cursor.to_digit(10) {
+            Some(value) => {
                 trace!(char = char_at_cursor.to_string(), "DIGIT");
                 calibration_digits.push(CalibrationDigit::AsDigit {
-                    value: char_at_cursor.to_string().parse::<u8>()?,
+                    value: value as u8,
                     range: cursor_pos..=cursor_pos,
                 });
             }
-            false => {
+            None => {
                 // Match first letter of all digit-words
                 for dw in DigitWord::all() {
                     if dw.str_value().starts_with(char_at_cursor) {
@@ -93,42 +102,29 @@ pub fn find_numbers(text: &str) -> anyhow::Result<Vec<u8>> {
         }
         match_start_positions.clear();
         match_start_positions.extend(next_start_positions.iter().map(|(k, v)| (*k, v.clone())));
-        next_start_positions = create_start_positions(text.len());
+        next_start_positions = create_start_positions(len);
         span_match.exit();
     }
 
     let calibration_digit_str = calibration_digits
         .iter()
-        .map(|r| {
-            let range = match r {
-                CalibrationDigit::AsDigit { range, .. } => range,
-                CalibrationDigit::AsWord { range, .. } => range,
-            };
-
-            format_text_span(text, range.clone())
-        })
+        .map(|cd| format_text_span(text, cd.range().clone()))
         .collect::<Vec<String>>()
         .join(", ");
 
     trace!(calibration_digits = calibration_digit_str, "FOUND");
-    Ok(calibration_digits
-        .iter()
-        .map(|cd| match cd {
-            CalibrationDigit::AsDigit { value, .. } => *value,
-            CalibrationDigit::AsWord { value, .. } => *value,
-        })
-        .collect())
+    Ok(calibration_digits)
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct MatchCandidate {
+pub(crate) struct MatchCandidate {
     start_pos: usize,
     word: Vec<char>,
     value: u8,
 }
 
 impl MatchCandidate {
-    #[cfg(test)]
+    #[cfg(any(test, feature = "fuzzing"))]
     pub fn new(start_pos: usize, word: &str, value: u8) -> Self {
         Self {
             start_pos,
@@ -177,21 +173,25 @@ impl Structable for MatchCandidate {
 }
 
 #[derive(Debug, Eq, PartialEq)]
-enum MatchResult {
+pub(crate) enum MatchResult {
     Discard,
     Complete(u8),
     Continue,
 }
 
 #[instrument(level = "trace", ret, skip(match_candidate), fields(word, word_pos))]
-fn check_match(
+pub(crate) fn check_match(
     cursor_pos: usize,
     char_at_cursor: &char,
     match_candidate: &MatchCandidate,
 ) -> MatchResult {
     tracing::Span::current().record("word", match_candidate.word_str());
     let candidate_word = match_candidate.word.clone();
-    let candidate_position_to_check = cursor_pos - match_candidate.start_pos;
+    // A candidate starting after the cursor has nothing to compare yet.
+    let Some(candidate_position_to_check) = cursor_pos.checked_sub(match_candidate.start_pos)
+    else {
+        return MatchResult::Discard;
+    };
     tracing::Span::current().record(
         "word_pos",
         format_text_span(
@@ -199,13 +199,11 @@ fn check_match(
             candidate_position_to_check..=candidate_position_to_check,
         ),
     );
-    let is_past_end_of_word = candidate_position_to_check >= candidate_word.len();
-    let still_matches = match is_past_end_of_word {
-        true => false,
-        false => *char_at_cursor == candidate_word[candidate_position_to_check],
-    };
+    // Also false past the end of the word, so an empty word never matches.
+    let still_matches = candidate_word.get(candidate_position_to_check) == Some(char_at_cursor);
 
-    let is_complete_match = candidate_position_to_check == candidate_word.len() - 1;
+    let is_complete_match =
+        candidate_word.len().checked_sub(1) == Some(candidate_position_to_check);
 
     match still_matches {
         false => MatchResult::Discard,
@@ -232,8 +230,14 @@ mod test {
     use aoc2023lib::init_logging;
     use ctor::ctor;
     use paste::paste;
+    use proptest::prelude::*;
 
-    use crate::find_numbers::{check_match, find_numbers, MatchCandidate, MatchResult};
+    use crate::calibration_digit::CalibrationDigit;
+    use crate::digit_word::DigitWord;
+    use crate::find_numbers::{
+        check_match, find_calibration_digits, find_numbers, MatchCandidate, MatchResult,
+    };
+    use crate::matcher::DigitWordMatcher;
 
     #[ctor]
     fn init() {
@@ -263,6 +267,8 @@ mod test {
         f: ("zoneight234", vec![1, 8, 2, 3, 4]),
         g: ("7pqrstsixteen", vec![7, 6]),
         h: ("7nineight", vec![7, 9, 8]),
+        non_ascii: ("½ünine٣7", vec![9, 7]),
+        non_ascii_word: ("zweïone", vec![1]),
     }
 
     macro_rules! test_check_match {
@@ -289,6 +295,23 @@ mod test {
         eight_2: ("fiveight", 5, MatchCandidate::new(3, "eight", 8), MatchResult::Continue),
         eight_3: ("fiveight", 6, MatchCandidate::new(3, "eight", 8), MatchResult::Continue),
         eight_4: ("fiveight", 7, MatchCandidate::new(3, "eight", 8), MatchResult::Complete(8)),
+        past_end: ("fiveight", 7, MatchCandidate::new(0, "five", 5), MatchResult::Discard),
+        before_start: ("fiveight", 2, MatchCandidate::new(3, "eight", 8), MatchResult::Discard),
+        empty_word: ("fiveight", 3, MatchCandidate::new(3, "", 0), MatchResult::Discard),
+    }
+
+    #[test]
+    fn test_check_match_cursor_at_max() {
+        let match_candidate = MatchCandidate::new(0, "five", 5);
+        assert_eq!(
+            MatchResult::Discard,
+            check_match(usize::MAX, &'x', &match_candidate)
+        );
+        let match_candidate = MatchCandidate::new(usize::MAX - 1, "ab", 1);
+        assert_eq!(
+            MatchResult::Complete(1),
+            check_match(usize::MAX, &'b', &match_candidate)
+        );
     }
 
     #[test]
@@ -298,4 +321,95 @@ mod test {
             find_numbers("fourzqlhcjksixthreejrl9").unwrap()
         )
     }
+
+    #[test]
+    fn test_find_calibration_digits_overlapping_words() {
+        assert_eq!(
+            find_calibration_digits("3eightwo").unwrap(),
+            vec![
+                CalibrationDigit::AsDigit {
+                    value: 3,
+                    range: 0..=0
+                },
+                CalibrationDigit::AsWord {
+                    value: 8,
+                    range: 1..=5
+                },
+                CalibrationDigit::AsWord {
+                    value: 2,
+                    range: 5..=7
+                },
+            ]
+        );
+    }
+
+    /// Tries every digit and digit word at every char position, sorted by where they end.
+    fn reference_calibration_digits(text: &str) -> Vec<CalibrationDigit> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut found: Vec<CalibrationDigit> = Vec::new();
+        for start in 0..chars.len() {
+            let rest: String = chars[start..].iter().collect();
+            for dw in DigitWord::all() {
+                let digit = dw.int_value().to_string();
+                let word = dw.str_value();
+                if rest.starts_with(digit.as_str()) {
+                    found.push(CalibrationDigit::AsDigit {
+                        value: dw.int_value(),
+                        range: start..=start,
+                    });
+                }
+                if rest.starts_with(word) {
+                    found.push(CalibrationDigit::AsWord {
+                        value: dw.int_value(),
+                        range: start..=start + word.chars().count() - 1,
+                    });
+                }
+            }
+        }
+        found.sort_by_key(|digit| *digit.range().end());
+        found
+    }
+
+    /// Digits, digit words, words glued together by a shared letter, word fragments and noise,
+    /// including chars that are several bytes long or numeric but not ASCII digits.
+    fn calibration_line() -> impl Strategy<Value = String> {
+        let token = prop_oneof![
+            "[0-9]",
+            prop::sample::select(
+                DigitWord::all()
+                    .map(|dw| dw.str_value().to_string())
+                    .to_vec()
+            ),
+            prop::sample::select(vec!["eightwo", "oneight", "twone", "sevenine", "fiveight"])
+                .prop_map(str::to_string),
+            prop::sample::select(vec![
+                "on", "tw", "thre", "fou", "fiv", "seve", "eigh", "nin"
+            ])
+            .prop_map(str::to_string),
+            "[a-z]{1,3}",
+            prop::sample::select(vec!["ä", "½", "٣", "日", "é"]).prop_map(str::to_string),
+        ];
+        prop::collection::vec(token, 0..12).prop_map(|tokens| tokens.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn test_find_calibration_digits_matches_reference(text in calibration_line()) {
+            prop_assert_eq!(find_calibration_digits(&text).unwrap(), reference_calibration_digits(&text));
+        }
+
+        #[test]
+        fn test_matcher_matches_reference(text in calibration_line()) {
+            prop_assert_eq!(DigitWordMatcher::new().find_all(&text), reference_calibration_digits(&text));
+        }
+
+        #[test]
+        fn test_find_numbers_are_reference_values(text in calibration_line()) {
+            let expected: Vec<u8> = reference_calibration_digits(&text)
+                .iter()
+                .map(CalibrationDigit::value)
+                .collect();
+            prop_assert_eq!(find_numbers(&text).unwrap(), expected);
+        }
+    }
 }
@@ -0,0 +1,72 @@
+use crate::find_numbers::{self, find_calibration_digits, MatchCandidate, MatchResult};
+use crate::matcher::DigitWordMatcher;
+
+/// Finds the digits in `data`, read as lossy UTF-8, with both matchers. Panics if
+/// `find_numbers` fails or the matchers disagree.
+pub fn find_numbers(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    let digits = find_calibration_digits(&text).expect("Every char has start positions");
+    assert_eq!(digits, DigitWordMatcher::new().find_all(&text));
+}
+
+/// Checks `char_at_cursor` against a candidate in any state, including ones `find_numbers`
+/// never creates. Panics if the result contradicts the candidate.
+pub fn check_match(
+    cursor_pos: usize,
+    char_at_cursor: char,
+    start_pos: usize,
+    word: &str,
+    value: u8,
+) {
+    let candidate = MatchCandidate::new(start_pos, word, value);
+    let word: Vec<char> = word.chars().collect();
+    let position = cursor_pos.checked_sub(start_pos);
+    let expected_char = position.and_then(|position| word.get(position));
+    match find_numbers::check_match(cursor_pos, &char_at_cursor, &candidate) {
+        MatchResult::Discard => assert_ne!(expected_char, Some(&char_at_cursor)),
+        MatchResult::Continue => {
+            assert_eq!(expected_char, Some(&char_at_cursor));
+            assert!(position.is_some_and(|position| position + 1 < word.len()));
+        }
+        MatchResult::Complete(complete) => {
+            assert_eq!(complete, value);
+            assert_eq!(expected_char, Some(&char_at_cursor));
+            assert_eq!(position.map(|position| position + 1), Some(word.len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use crate::fuzzing::{check_match, find_numbers};
+
+    proptest! {
+        #[test]
+        fn test_find_numbers_does_not_panic(data in prop::collection::vec(any::<u8>(), 0..64)) {
+            find_numbers(&data);
+        }
+
+        #[test]
+        fn test_check_match_does_not_panic(
+            cursor_pos in prop_oneof![
+                0..8usize,
+                Just(usize::MAX),
+                Just(usize::MAX - 1),
+                any::<usize>()
+            ],
+            char_at_cursor in prop_oneof![prop::char::range('e', 'i'), any::<char>()],
+            start_pos in prop_oneof![
+                0..8usize,
+                Just(usize::MAX),
+                Just(usize::MAX - 1),
+                any::<usize>()
+            ],
+            word in prop_oneof![Just("five".to_string()), "[e-i]{0,4}", any::<String>()],
+            value in any::<u8>(),
+        ) {
+            check_match(cursor_pos, char_at_cursor, start_pos, &word, value);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod calibration_digit;
+pub mod digit_word;
+pub mod explain;
+pub mod find_numbers;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
+pub mod matcher;
+pub mod pipeline;
+pub mod vocabulary;
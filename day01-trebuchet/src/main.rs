@@ -1,53 +1,73 @@
 extern crate core;
 
-use std::iter::Iterator;
-use std::string::ToString;
+use std::io;
 
-use anyhow::Result;
-use find_numbers::find_numbers;
-use tracing::instrument;
-
-pub mod calibration_digit;
-pub mod digit_word;
-mod find_numbers;
-pub mod utils;
+use anyhow::{anyhow, Context, Result};
 
+use aoc2023lib::terminal::ColorMode;
 use aoc2023lib::{init_logging, read_lines};
+use day01_trebuchet::calibration_digit::CalibrationDigit;
+use day01_trebuchet::explain::explain_line;
+use day01_trebuchet::matcher::{DigitWordMatcher, MatchMode};
+use day01_trebuchet::pipeline::{calibration_value_in_mode, sum_calibration_values};
+use day01_trebuchet::vocabulary::Vocabulary;
+
+const INPUT: &str = "day01-trebuchet/input";
 
 fn main() -> Result<()> {
     color_backtrace::install();
     init_logging();
-    let lines: Vec<String> = read_lines("day01-trebuchet/input")
-        .expect("Error reading file")
-        .collect::<Result<_, _>>()
-        .expect("Error reading lines");
-    let x = extract_calibration_value(lines);
-
-    let sum: u64 = x
-        .iter()
-        .map(|l| l.parse::<u64>().expect("Could not parse int"))
-        .sum();
-    println!("sum: {}", sum);
-    Ok(())
-}
 
-#[instrument(skip(lines))]
-pub fn extract_calibration_value(lines: Vec<String>) -> Vec<String> {
-    lines
-        .iter()
-        .filter_map(|line| {
-            let numbers = find_numbers(line).unwrap();
-            extract_two_digit_number(numbers)
-        })
-        .collect()
+    // `--vocabulary <path>` replaces the English digit words with the ones in the file.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let vocabulary = match args.iter().position(|arg| arg == "--vocabulary") {
+        Some(index) => Vocabulary::load(
+            args.get(index + 1)
+                .ok_or_else(|| anyhow!("--vocabulary needs a path"))?,
+        )?,
+        None => Vocabulary::default(),
+    };
+    let matcher = DigitWordMatcher::with_vocabulary(&vocabulary);
+    if args.iter().any(|arg| arg == "--explain") {
+        let lines = read_lines(INPUT).context("Could not read input")?;
+        explain(lines, &matcher, MatchMode::Both)?;
+    }
+
+    let parts = [(1, MatchMode::Digits), (2, MatchMode::Both)];
+    let modes = parts.map(|(_, mode)| mode);
+    let lines = read_lines(INPUT).context("Could not read input")?;
+    let sums = sum_calibration_values(lines, &matcher, &modes)?;
+    for ((part, mode), sum) in parts.iter().zip(sums) {
+        println!("part {} ({}) sum: {}", part, mode, sum);
+    }
+    Ok(())
 }
 
-#[instrument(ret)]
-fn extract_two_digit_number(numbers: Vec<u8>) -> Option<String> {
-    match *numbers.as_slice() {
-        [a] => Some([a, a]),
-        [a, .., b] => Some([a, b]),
-        _ => None,
+/// Prints every line with the digits its calibration value is made of underlined, the value
+/// and the running sum.
+fn explain<I>(lines: I, matcher: &DigitWordMatcher, mode: MatchMode) -> Result<()>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let color_mode = ColorMode::detect();
+    let mut sum = 0;
+    for (index, line) in lines.enumerate() {
+        let line = line.context("Could not read line")?;
+        let digits: Vec<CalibrationDigit> = matcher
+            .find_all(&line)
+            .into_iter()
+            .filter(|digit| mode.accepts(digit))
+            .collect();
+        println!("line {}:", index + 1);
+        println!("{}", explain_line(&line, &digits, color_mode));
+        match calibration_value_in_mode(&digits, mode) {
+            Some(value) => {
+                sum += value;
+                println!("= {} (sum {})", value, sum);
+            }
+            None => println!("= no digits (sum {})", sum),
+        }
+        println!();
     }
-    .map(|pair| String::from_iter(pair.map(|c| c.to_string())))
+    Ok(())
 }
@@ -0,0 +1,230 @@
+use std::fmt::{Display, Formatter};
+
+use aho_corasick::AhoCorasick;
+use tracing::instrument;
+
+use crate::calibration_digit::CalibrationDigit;
+use crate::vocabulary::Vocabulary;
+
+/// Which kinds of digits count: part one only looks at digits, part two at words too.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MatchMode {
+    Digits,
+    Words,
+    Both,
+}
+
+impl MatchMode {
+    pub fn accepts(&self, digit: &CalibrationDigit) -> bool {
+        match self {
+            MatchMode::Digits => !digit.is_word(),
+            MatchMode::Words => digit.is_word(),
+            MatchMode::Both => true,
+        }
+    }
+}
+
+impl Display for MatchMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MatchMode::Digits => "digits",
+            MatchMode::Words => "words",
+            MatchMode::Both => "digits and words",
+        })
+    }
+}
+
+/// Finds all digits and digit words in a text, overlapping ones like `eightwo` included, with
+/// an Aho–Corasick automaton in a single pass over the text.
+#[derive(Debug, Clone)]
+pub struct DigitWordMatcher {
+    automaton: AhoCorasick,
+    word_values: Vec<u8>,
+}
+
+impl DigitWordMatcher {
+    /// A matcher for the English digit words.
+    pub fn new() -> Self {
+        Self::with_vocabulary(&Vocabulary::english())
+    }
+
+    /// Patterns `0` to `9` are the digits, the ones after that the words of `vocabulary`.
+    pub fn with_vocabulary(vocabulary: &Vocabulary) -> Self {
+        let digits = (0..10).map(|digit: u8| digit.to_string());
+        let words = vocabulary
+            .words()
+            .iter()
+            .map(|(pattern, _)| pattern.clone());
+        let automaton =
+            AhoCorasick::new(digits.chain(words)).expect("Vocabulary patterns are valid");
+        Self {
+            automaton,
+            word_values: vocabulary.words().iter().map(|(_, value)| *value).collect(),
+        }
+    }
+
+    /// Every digit and digit word in `text` in the order they end, with ranges in chars.
+    pub fn find_all(&self, text: &str) -> Vec<CalibrationDigit> {
+        let mut char_positions = vec![0; text.len() + 1];
+        for (char_pos, (byte_pos, c)) in text.char_indices().enumerate() {
+            char_positions[byte_pos..byte_pos + c.len_utf8()].fill(char_pos);
+        }
+
+        self.automaton
+            .find_overlapping_iter(text)
+            .map(|found| {
+                let range = char_positions[found.start()]..=char_positions[found.end() - 1];
+                match found.pattern().as_usize() {
+                    digit @ 0..=9 => CalibrationDigit::AsDigit {
+                        value: digit as u8,
+                        range,
+                    },
+                    word => CalibrationDigit::AsWord {
+                        value: self.word_values[word - 10],
+                        range,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    #[instrument(ret, level = "info", skip(self))]
+    pub fn find_numbers(&self, text: &str) -> Vec<u8> {
+        self.find_numbers_in_mode(text, MatchMode::Both)
+    }
+
+    /// The values of the digits in `text` that `mode` accepts.
+    pub fn find_numbers_in_mode(&self, text: &str, mode: MatchMode) -> Vec<u8> {
+        self.find_all(text)
+            .iter()
+            .filter(|digit| mode.accepts(digit))
+            .map(CalibrationDigit::value)
+            .collect()
+    }
+}
+
+impl Default for DigitWordMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aoc2023lib::{init_logging, read_lines};
+    use ctor::ctor;
+    use paste::paste;
+
+    use crate::calibration_digit::CalibrationDigit;
+    use crate::find_numbers::find_calibration_digits;
+    use crate::matcher::{DigitWordMatcher, MatchMode};
+    use crate::vocabulary::Vocabulary;
+
+    #[ctor]
+    fn init() {
+        init_logging();
+    }
+
+    macro_rules! test_matcher_find_numbers {
+        ($($name:ident: $value:expr,)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<test_matcher_find_numbers_ $name>]() {
+                        let (input, expected) = $value;
+                        assert_eq!(expected, DigitWordMatcher::new().find_numbers(input));
+                    }
+                }
+            )*
+        }
+    }
+
+    test_matcher_find_numbers! {
+        a: ("two1nine", vec![2, 1, 9]),
+        b: ("eightwothree", vec![8, 2, 3]),
+        c: ("abcone2threexyz", vec![1, 2, 3]),
+        d: ("xtwone3four", vec![2, 1, 3, 4]),
+        e: ("4nineeightseven2", vec![4, 9, 8, 7, 2]),
+        f: ("zoneight234", vec![1, 8, 2, 3, 4]),
+        g: ("7pqrstsixteen", vec![7, 6]),
+        h: ("7nineight", vec![7, 9, 8]),
+        empty: ("", Vec::<u8>::new()),
+    }
+
+    #[test]
+    fn test_ranges_are_in_chars() {
+        let found = DigitWordMatcher::new().find_all("äone7");
+        assert_eq!(
+            found,
+            vec![
+                CalibrationDigit::AsWord {
+                    value: 1,
+                    range: 1..=3
+                },
+                CalibrationDigit::AsDigit {
+                    value: 7,
+                    range: 4..=4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let matcher = DigitWordMatcher::new();
+        assert_eq!(
+            matcher.find_numbers_in_mode("xtwone3four", MatchMode::Digits),
+            vec![3]
+        );
+        assert_eq!(
+            matcher.find_numbers_in_mode("xtwone3four", MatchMode::Words),
+            vec![2, 1, 4]
+        );
+        assert_eq!(
+            matcher.find_numbers_in_mode("xtwone3four", MatchMode::Both),
+            vec![2, 1, 3, 4]
+        );
+        assert!(matcher
+            .find_numbers_in_mode("eightwo", MatchMode::Digits)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_custom_vocabulary() {
+        let vocabulary = Vocabulary::english()
+            .word("ten", 10)
+            .word("eleven", 11)
+            .word("drei", 3);
+        let matcher = DigitWordMatcher::with_vocabulary(&vocabulary);
+        assert_eq!(
+            matcher.find_numbers("elevenine4tendrei"),
+            vec![11, 9, 4, 10, 3]
+        );
+
+        let matcher = DigitWordMatcher::with_vocabulary(&Vocabulary::new().word("IV", 4));
+        assert_eq!(matcher.find_numbers("oneIV2"), vec![4, 2]);
+    }
+
+    #[test]
+    fn test_matches_find_calibration_digits_on_non_ascii() {
+        let matcher = DigitWordMatcher::new();
+        for line in ["½ünine٣7", "ääeightwo", "ⅷseven€1", "öne", "日本two三4"] {
+            assert_eq!(
+                find_calibration_digits(line).unwrap(),
+                matcher.find_all(line)
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_find_calibration_digits_on_input() {
+        let matcher = DigitWordMatcher::new();
+        for line in read_lines(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap() {
+            let line = line.unwrap();
+            assert_eq!(
+                find_calibration_digits(&line).unwrap(),
+                matcher.find_all(&line)
+            );
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::io;
+
+use anyhow::Result;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+
+use crate::calibration_digit::CalibrationDigit;
+use crate::matcher::{DigitWordMatcher, MatchMode};
+
+/// The first and last of `numbers` written next to each other, e.g. 29 for `[2, 1, 9]` and 77
+/// for `[7]`.
+pub fn calibration_value(numbers: &[u8]) -> Option<u64> {
+    let (first, last) = match *numbers {
+        [] => return None,
+        [a] => (a, a),
+        [a, .., b] => (a, b),
+    };
+    let shift = 10u64.pow(last.checked_ilog10().unwrap_or(0) + 1);
+    Some(first as u64 * shift + last as u64)
+}
+
+/// The calibration value of `digits`, only counting the ones `mode` accepts.
+pub fn calibration_value_in_mode(digits: &[CalibrationDigit], mode: MatchMode) -> Option<u64> {
+    let numbers: Vec<u8> = digits
+        .iter()
+        .filter(|digit| mode.accepts(digit))
+        .map(CalibrationDigit::value)
+        .collect();
+    calibration_value(&numbers)
+}
+
+/// Sums the calibration values of `lines` once for every mode in `modes`. Lines are read as
+/// the rayon workers ask for them, so the input is never held in memory as a whole.
+pub fn sum_calibration_values<I>(
+    lines: I,
+    matcher: &DigitWordMatcher,
+    modes: &[MatchMode],
+) -> Result<Vec<u64>>
+where
+    I: Iterator<Item = io::Result<String>> + Send,
+{
+    lines
+        .par_bridge()
+        .map(|line| -> Result<Vec<u64>> {
+            let digits = matcher.find_all(line?.as_str());
+            Ok(modes
+                .iter()
+                .map(|mode| calibration_value_in_mode(&digits, *mode).unwrap_or(0))
+                .collect())
+        })
+        .try_reduce(
+            || vec![0; modes.len()],
+            |sums, values| Ok(sums.iter().zip(values).map(|(s, v)| s + v).collect()),
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use crate::matcher::{DigitWordMatcher, MatchMode};
+    use crate::pipeline::{calibration_value, sum_calibration_values};
+
+    #[test]
+    fn test_calibration_value() {
+        assert_eq!(calibration_value(&[]), None);
+        assert_eq!(calibration_value(&[7]), Some(77));
+        assert_eq!(calibration_value(&[2, 1, 9]), Some(29));
+        assert_eq!(calibration_value(&[0, 0]), Some(0));
+        assert_eq!(calibration_value(&[1, 10]), Some(110));
+    }
+
+    fn lines(text: &str) -> impl Iterator<Item = io::Result<String>> + Send + '_ {
+        text.lines().map(|line| Ok(line.to_string()))
+    }
+
+    #[test]
+    fn test_sum_calibration_values_per_mode() {
+        let matcher = DigitWordMatcher::new();
+        let modes = [MatchMode::Digits, MatchMode::Both];
+        let part_one = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        assert_eq!(
+            sum_calibration_values(lines(part_one), &matcher, &[MatchMode::Digits]).unwrap(),
+            vec![142]
+        );
+        let part_two = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                        4nineeightseven2\nzoneight234\n7pqrstsixteen";
+        assert_eq!(
+            sum_calibration_values(lines(part_two), &matcher, &modes).unwrap(),
+            vec![11 + 22 + 33 + 42 + 24 + 77, 281]
+        );
+    }
+
+    #[test]
+    fn test_sum_calibration_values_read_error() {
+        let lines = vec![Ok("1".to_string()), Err(io::Error::other("broken"))];
+        let result = sum_calibration_values(
+            lines.into_iter(),
+            &DigitWordMatcher::new(),
+            &[MatchMode::Both],
+        );
+        assert!(result.is_err());
+    }
+}
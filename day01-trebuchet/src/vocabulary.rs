@@ -0,0 +1,141 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::digit_word::DigitWord;
+
+/// The words a [`crate::matcher::DigitWordMatcher`] looks for besides the digits, each with
+/// the value it stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u8)>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self { words: Vec::new() }
+    }
+
+    /// The English digit words, `zero` to `nine`.
+    pub fn english() -> Self {
+        DigitWord::all().iter().fold(Self::new(), |vocabulary, dw| {
+            vocabulary.word(dw.str_value(), dw.int_value())
+        })
+    }
+
+    /// Adds `pattern`, replacing the value of a pattern that was already added. Empty
+    /// patterns are ignored.
+    pub fn word(mut self, pattern: &str, value: u8) -> Self {
+        if pattern.is_empty() {
+            return self;
+        }
+        match self.words.iter_mut().find(|(p, _)| p == pattern) {
+            Some((_, current)) => *current = value,
+            None => self.words.push((pattern.to_string(), value)),
+        }
+        self
+    }
+
+    /// Adds all words of `other`, its values win for patterns in both.
+    pub fn extend(self, other: &Vocabulary) -> Self {
+        other
+            .words
+            .iter()
+            .fold(self, |vocabulary, (pattern, value)| {
+                vocabulary.word(pattern, *value)
+            })
+    }
+
+    pub fn words(&self) -> &[(String, u8)] {
+        self.words.as_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Reads one `pattern value` pair per line, e.g. `eins 1`. Blank lines and lines starting
+    /// with `#` are skipped.
+    pub fn read_from<B: BufRead>(reader: B) -> Result<Self> {
+        let mut vocabulary = Self::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (pattern, value) = parse_word(line)
+                .with_context(|| format!("Invalid word on line {}: {:?}", index + 1, line))?;
+            vocabulary = vocabulary.word(pattern, value);
+        }
+        Ok(vocabulary)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+        Self::read_from(BufReader::new(file))
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+fn parse_word(line: &str) -> Result<(&str, u8)> {
+    let (pattern, value) = line
+        .rsplit_once(char::is_whitespace)
+        .ok_or_else(|| anyhow!("Missing value"))?;
+    let pattern = pattern.trim_end();
+    if pattern.is_empty() {
+        return Err(anyhow!("Missing pattern"));
+    }
+    Ok((pattern, value.parse::<u8>()?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vocabulary::Vocabulary;
+
+    #[test]
+    fn test_english_is_default() {
+        let vocabulary = Vocabulary::default();
+        assert_eq!(vocabulary.len(), 10);
+        assert_eq!(vocabulary.words()[7], ("seven".to_string(), 7));
+    }
+
+    #[test]
+    fn test_word_replaces_value() {
+        let vocabulary = Vocabulary::new().word("one", 1).word("", 0).word("one", 11);
+        assert_eq!(vocabulary.words(), &[("one".to_string(), 11)]);
+    }
+
+    #[test]
+    fn test_read_from() {
+        let text = "# German\neins 1\n\n  zwei   2\nein und zwanzig 21\n";
+        let vocabulary = Vocabulary::read_from(text.as_bytes()).unwrap();
+        assert_eq!(
+            vocabulary.words(),
+            &[
+                ("eins".to_string(), 1),
+                ("zwei".to_string(), 2),
+                ("ein und zwanzig".to_string(), 21)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_from_reports_line() {
+        let error = Vocabulary::read_from("eins 1\nzwei\n".as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        assert!(Vocabulary::read_from("eins 256".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::ops::Index;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use grid::Grid;
+
+use aoc2023lib::color::{Color, Theme};
+use aoc2023lib::position::Position;
+use aoc2023lib::visualize::Visualizer;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Value {
+    Blank,
+    Symbol(char),
+    Digit(u8),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Highlight {
+    Symbol,
+    PartNumber,
+    PartNumberCompletion,
+    GearSymbol,
+}
+
+impl Display for Highlight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Highlight::Symbol => "symbol",
+            Highlight::PartNumber => "part-number",
+            Highlight::PartNumberCompletion => "part-number-completion",
+            Highlight::GearSymbol => "gear-symbol",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Highlight {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "symbol" => Ok(Highlight::Symbol),
+            "part-number" => Ok(Highlight::PartNumber),
+            "part-number-completion" => Ok(Highlight::PartNumberCompletion),
+            "gear-symbol" => Ok(Highlight::GearSymbol),
+            other => Err(anyhow!("Unknown highlight {:?}", other)),
+        }
+    }
+}
+
+impl Highlight {
+    pub const ALL: [Highlight; 4] = [
+        Highlight::Symbol,
+        Highlight::PartNumber,
+        Highlight::PartNumberCompletion,
+        Highlight::GearSymbol,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Highlight::Symbol => "symbol",
+            Highlight::PartNumber => "part number",
+            Highlight::PartNumberCompletion => "searching part number",
+            Highlight::GearSymbol => "gear (two part numbers)",
+        }
+    }
+
+    /// Brackets around highlighted cells in terminal output without colors.
+    pub fn marker(&self) -> (char, char) {
+        match self {
+            Highlight::Symbol => ('<', '>'),
+            Highlight::PartNumber => ('[', ']'),
+            Highlight::PartNumberCompletion => ('(', ')'),
+            Highlight::GearSymbol => ('{', '}'),
+        }
+    }
+
+    pub fn color(&self, theme: &Theme) -> Color {
+        match self {
+            Highlight::Symbol => theme.accent(0),
+            Highlight::PartNumber => theme.accent(1),
+            Highlight::PartNumberCompletion => theme.accent(2),
+            Highlight::GearSymbol => theme.accent(3),
+        }
+    }
+}
+
+pub struct Solution {
+    pub part_number_sum: i32,
+    pub gear_ratio_sum: i32,
+}
+
+pub struct Solver {
+    grid: Grid<Value>,
+}
+
+impl Solver {
+    pub fn new(grid: Grid<Value>) -> Self {
+        Self { grid }
+    }
+
+    pub fn run(&self, visualizer: &mut dyn Visualizer<Highlight>) -> Result<Solution> {
+        let mut part_number_sum = 0;
+        let mut gear_ratio_sum = 0;
+
+        for symbol_position in self.find_symbols() {
+            let mut part_numbers_for_symbol: Vec<PartNumber> = vec![];
+            visualizer.focus(symbol_position)?;
+            visualizer.checkpoint()?;
+            visualizer.highlight(symbol_position, Highlight::Symbol)?;
+            for part_number_positions in self.find_part_numbers(symbol_position, visualizer)? {
+                for pos in part_number_positions.clone() {
+                    visualizer.highlight(pos, Highlight::PartNumber)?;
+                }
+
+                let part_number =
+                    PartNumber::from_grid_positions(&self.grid, part_number_positions)?;
+                part_number_sum += part_number.number;
+                part_numbers_for_symbol.push(part_number);
+                visualizer.counter("part number sum", part_number_sum as i64)?;
+                visualizer.checkpoint()?;
+            }
+            visualizer.checkpoint()?;
+
+            if let Some(Value::Symbol('*')) = symbol_position.grid_value(&self.grid) {
+                if part_numbers_for_symbol.len() == 2 {
+                    visualizer.highlight(symbol_position, Highlight::GearSymbol)?;
+                    gear_ratio_sum +=
+                        part_numbers_for_symbol[0].number * part_numbers_for_symbol[1].number;
+                    visualizer.counter("gear ratio sum", gear_ratio_sum as i64)?;
+                    visualizer.checkpoint()?;
+                }
+            }
+        }
+
+        Ok(Solution {
+            part_number_sum,
+            gear_ratio_sum,
+        })
+    }
+
+    fn find_part_numbers(
+        &self,
+        symbol_position: Position,
+        visualizer: &mut dyn Visualizer<Highlight>,
+    ) -> Result<Vec<Vec<Position>>> {
+        let mut visited_positions: HashSet<Position> = HashSet::new();
+        let mut part_numbers: Vec<Vec<Position>> = Vec::new();
+        for pos in get_neighbor_positions(&self.grid, symbol_position) {
+            visualizer.visit(pos)?;
+            if let Some(Value::Digit(_)) = pos.grid_value(&self.grid) {
+                if !visited_positions.contains(&pos) {
+                    visited_positions.insert(pos);
+                    let connected_numbers = self.complete_part_number(pos, visualizer)?;
+                    for cp in connected_numbers.clone() {
+                        visited_positions.insert(cp);
+                    }
+                    part_numbers.push(connected_numbers);
+                }
+            }
+        }
+        Ok(part_numbers)
+    }
+
+    fn complete_part_number(
+        &self,
+        symbol_position: Position,
+        visualizer: &mut dyn Visualizer<Highlight>,
+    ) -> Result<Vec<Position>> {
+        let mut pos = symbol_position;
+        let mut positions: HashSet<Position> = HashSet::new();
+        while let Some(Value::Digit(_)) = pos.grid_value(&self.grid) {
+            visualizer.visit(pos)?;
+            if !positions.contains(&pos) {
+                visualizer.highlight(pos, Highlight::PartNumberCompletion)?;
+                visualizer.checkpoint()?;
+            }
+            positions.insert(pos);
+            if pos.x() == 0 {
+                break;
+            }
+            pos = Position::new(pos.x() - 1, pos.y());
+        }
+        pos = symbol_position;
+        while let Some(Value::Digit(_)) = pos.grid_value(&self.grid) {
+            visualizer.visit(pos)?;
+            if !positions.contains(&pos) {
+                visualizer.highlight(pos, Highlight::PartNumberCompletion)?;
+                visualizer.checkpoint()?;
+            }
+            positions.insert(pos);
+            pos = Position::new(pos.x() + 1, pos.y());
+        }
+        Ok(positions.into_iter().collect())
+    }
+
+    fn find_symbols(&self) -> impl Iterator<Item = Position> + '_ {
+        self.grid.iter_rows().enumerate().flat_map(|(y, row)| {
+            row.enumerate().filter_map(move |(x, value)| match value {
+                Value::Blank => None,
+                Value::Symbol(_) => Some(Position::new(x, y)),
+                Value::Digit(_) => None,
+            })
+        })
+    }
+}
+
+pub fn glyph(value: &Value) -> String {
+    match value {
+        Value::Blank => ".".to_string(),
+        Value::Symbol(c) => c.to_string(),
+        Value::Digit(digit) => digit.to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartNumber {
+    pub number: i32,
+    #[allow(dead_code)]
+    positions: Vec<Position>,
+}
+
+impl PartNumber {
+    pub fn from_grid_positions(grid: &Grid<Value>, positions: Vec<Position>) -> Result<Self> {
+        let mut sorted_positions = positions.clone();
+        sorted_positions.sort_by_key(|pos| pos.x());
+
+        let mut numbers: Vec<u8> = Vec::new();
+
+        for pos in sorted_positions.clone() {
+            match pos.grid_value(grid) {
+                Some(Value::Digit(value)) => {
+                    numbers.push(*value);
+                }
+                other => Err(anyhow!(
+                    "Expected number at position {:?}, got {:?}",
+                    pos,
+                    other
+                ))?,
+            }
+        }
+
+        let str: String = numbers
+            .iter()
+            .map(|x| format!("{}", x))
+            .collect::<Vec<_>>()
+            .join("");
+        let number = str.parse::<i32>()?;
+        Ok(Self {
+            number,
+            positions: sorted_positions,
+        })
+    }
+}
+
+fn get_neighbor_positions(grid: &Grid<Value>, position: Position) -> Vec<Position> {
+    let mut neighbors: Vec<Position> = Vec::new();
+    for x_offset in -1i8..=1 {
+        for y_offset in -1i8..=1 {
+            if (x_offset, y_offset) != (0, 0) {
+                let neighbor: Position = Position::new(
+                    (position.x() as isize + x_offset as isize) as usize,
+                    (position.y() as isize + y_offset as isize) as usize,
+                );
+                if (0..=grid.cols()).contains(&neighbor.x())
+                    && (0..=grid.rows()).contains(&neighbor.y())
+                {
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+pub fn grid_from_lines<'a, I>(lines: I) -> Result<Grid<Value>>
+where
+    I: IntoIterator<Item = &'a str> + Index<usize, Output = &'a str> + Clone,
+{
+    let cols = lines[0].len();
+    for line in lines.clone() {
+        if line.len() != cols {
+            panic!(
+                "Expected lines to be of length {}, but {} was of length {}",
+                cols,
+                line,
+                line.len()
+            )
+        }
+    }
+
+    let char_vec = lines
+        .into_iter()
+        .flat_map(|line| {
+            line.chars()
+                .map(|c| match c.is_ascii_digit() {
+                    true => Value::Digit(c.to_string().parse::<u8>().unwrap()),
+                    false => match c {
+                        '.' => Value::Blank,
+                        c => Value::Symbol(c),
+                    },
+                })
+                .collect::<Vec<Value>>()
+        })
+        .collect();
+
+    Ok(Grid::from_vec(char_vec, cols))
+}
+
+#[cfg(test)]
+mod test {
+    use aoc2023lib::visualize::{EventLog, NoopVisualizer};
+
+    use crate::solver::{grid_from_lines, Highlight, Solver, Value};
+
+    const EXAMPLE: [&str; 10] = [
+        "467..114..",
+        "...*......",
+        "..35..633.",
+        "......#...",
+        "617*......",
+        ".....+.58.",
+        "..592.....",
+        "......755.",
+        "...$.*....",
+        ".664.598..",
+    ];
+
+    #[test]
+    fn test_solve_example() {
+        let grid = grid_from_lines(EXAMPLE.to_vec()).unwrap();
+        let solution = Solver::new(grid).run(&mut NoopVisualizer).unwrap();
+        assert_eq!(solution.part_number_sum, 4361);
+        assert_eq!(solution.gear_ratio_sum, 467835);
+    }
+
+    #[test]
+    fn test_highlight_round_trips_through_string() {
+        for highlight in Highlight::ALL {
+            assert_eq!(
+                highlight.to_string().parse::<Highlight>().unwrap(),
+                highlight
+            );
+        }
+    }
+
+    #[test]
+    fn test_visualizer_does_not_change_solution() {
+        let grid = grid_from_lines(EXAMPLE.to_vec()).unwrap();
+        let mut log = EventLog::new();
+        let solution = Solver::new(grid).run(&mut log).unwrap();
+        assert_eq!(solution.part_number_sum, 4361);
+        assert!(!log.is_empty());
+    }
+
+    #[test]
+    fn test_grid_from_lines() {
+        let lines = vec!["abc123", "456..."];
+        let grid = grid_from_lines(lines).unwrap();
+
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.cols(), 6);
+        assert_eq!(grid.get(0, 3), Some(&Value::Digit(1)));
+        assert_eq!(grid.get(1, 0), Some(&Value::Digit(4)));
+    }
+}
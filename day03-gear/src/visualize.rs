@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::stdout;
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use cairo::Context;
+use grid::Grid;
+
+use aoc2023lib::color::Theme;
+use aoc2023lib::draw::camera::{Camera, Easing};
+use aoc2023lib::draw::frames::FrameWriter;
+use aoc2023lib::draw::grid::GridRenderer;
+use aoc2023lib::draw::heatmap::Heatmap;
+use aoc2023lib::draw::legend::Legend;
+use aoc2023lib::draw::minimap::{Corner, Minimap};
+use aoc2023lib::draw::timeline::{EventKind, Timeline};
+use aoc2023lib::html::HtmlExporter;
+use aoc2023lib::terminal::{ColorMode, TerminalGridRenderer, TerminalVisualizer};
+use aoc2023lib::visualize::{Event, EventLog};
+
+use day03_gear::solver::{glyph, Highlight, Solver, Value};
+
+const TRACE_PATH: &str = "scratch/day03/trace.txt";
+
+/// Gears and updated sums are held on screen, runs of plain symbol checks are sped through.
+fn timeline() -> Timeline<'static, Highlight> {
+    Timeline::create()
+        .duration(EventKind::Focus, 2)
+        .key_frames(12, |event| {
+            matches!(
+                event,
+                Event::Counter { .. }
+                    | Event::Highlight {
+                        role: Highlight::GearSymbol,
+                        ..
+                    }
+            )
+        })
+        .fast_forward(3, 4)
+}
+
+fn frame_writer(
+    grid: Grid<Value>,
+    trace: &EventLog<Highlight>,
+    theme: Theme,
+) -> Result<FrameWriter<'static, Value, Highlight>> {
+    let renderer = GridRenderer::create(glyph).theme(&theme);
+    let camera = Camera::create(800., 600.)
+        .easing(Easing::CubicInOut)
+        .transition_frames(8)
+        .background(theme.background);
+    let focus_color = theme.highlight;
+    let legend = Highlight::ALL
+        .iter()
+        .fold(Legend::create(), |legend, highlight| {
+            legend.entry(highlight.color(&theme), highlight.description())
+        });
+    Ok(FrameWriter::create(
+        grid,
+        renderer,
+        move |highlight: &Highlight| highlight.color(&theme),
+        "scratch/day03/focused",
+    )?
+    .camera(camera)
+    .minimap(Minimap::create(200.))
+    .legend(legend)
+    .focus_color(focus_color)
+    .schedule(timeline().schedule(trace)))
+}
+
+fn write_heatmap(
+    grid: &Grid<Value>,
+    trace: &EventLog<Highlight>,
+    theme: &Theme,
+    path: &str,
+) -> Result<()> {
+    let mut heatmap = Heatmap::for_grid(grid).logarithmic();
+    trace.replay(&mut heatmap)?;
+
+    let renderer = GridRenderer::create(glyph).theme(theme);
+    let surface = renderer.create_surface(grid)?;
+    let context = Context::new(&surface)?;
+    renderer.draw(&context, grid)?;
+    heatmap.draw(&context, &renderer)?;
+    heatmap.legend("visits", 5).corner(Corner::TopRight).draw(
+        &context,
+        surface.width() as f64,
+        surface.height() as f64,
+    )?;
+    drop(context);
+
+    let mut file = File::create(path).with_context(|| format!("Could not create {}", path))?;
+    surface
+        .write_to_png(&mut file)
+        .with_context(|| format!("Could not write heatmap to {}", path))?;
+    Ok(())
+}
+
+/// Solves while recording a trace, or loads one with `--replay <trace>`, and renders it as
+/// an HTML viewer (`--html <path>`), a heatmap of visited cells (`--heatmap <path>`), in the
+/// terminal (`--terminal`) or as PNG frames.
+pub fn run(grid: Grid<Value>, args: &[String]) -> Result<()> {
+    let option = |name: &str| -> Result<Option<&String>> {
+        match args.iter().position(|arg| arg == name) {
+            Some(index) => Ok(Some(
+                args.get(index + 1)
+                    .ok_or_else(|| anyhow!("{} needs a path", name))?,
+            )),
+            None => Ok(None),
+        }
+    };
+
+    let trace: EventLog<Highlight> = match option("--replay")? {
+        Some(path) => EventLog::load(path)?,
+        None => {
+            let mut trace = EventLog::new();
+            let solution = Solver::new(grid.clone()).run(&mut trace)?;
+            println!("Sum is {}", solution.part_number_sum);
+            println!("Gear ratio sum is {}", solution.gear_ratio_sum);
+            trace.save(TRACE_PATH)?;
+            trace
+        }
+    };
+
+    let theme = Theme::light();
+    if let Some(path) = option("--heatmap")? {
+        return write_heatmap(&grid, &trace, &theme, path);
+    }
+    if let Some(path) = option("--html")? {
+        return HtmlExporter::create(glyph, move |highlight: &Highlight| highlight.color(&theme))
+            .title("Day 3: Gear Ratios")
+            .theme(&theme)
+            .viewport(40, 30)
+            .frame_delay(50)
+            .write(&grid, &trace, path);
+    }
+    match args.iter().any(|arg| arg == "--terminal") {
+        true => {
+            let color_mode = ColorMode::detect();
+            // Room for the markers around glyphs when there are no colors.
+            let cell_width = match color_mode {
+                ColorMode::Plain => 3,
+                _ => 1,
+            };
+            let renderer = TerminalGridRenderer::create(glyph)
+                .color_mode(color_mode)
+                .cell_width(cell_width)
+                .theme(&theme);
+            let mut terminal = TerminalVisualizer::create(
+                grid,
+                renderer,
+                move |highlight: &Highlight| highlight.color(&theme),
+                stdout(),
+            )
+            .markers(Highlight::marker)
+            .viewport(40, 20)
+            .clear();
+            trace.replay(&mut terminal)
+        }
+        false => trace.replay(&mut frame_writer(grid, &trace, theme)?),
+    }
+}